@@ -1,19 +1,41 @@
-use util::{FromLine, FromLines, read, run};
+use std::mem;
 
-mod util;
+use crate::util::{ParseResult, Solution, TryFromLine, TryFromLines};
+use crate::util::stream::{StreamSolution, TryFromLineStream};
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day1.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+// Elves carrying the most calories, for part 2.
+const TOP_COUNT: usize = 3;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Input;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn part_1(input: &Input) -> u64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u64 {
+        input.part_2()
+    }
+}
+
+impl StreamSolution for Day1 {
+    type Stream = TopElves;
+
+    fn stream_part_1(stream: &TopElves) -> u64 {
+        stream.top[0]
+    }
+
+    fn stream_part_2(stream: &TopElves) -> u64 {
+        stream.top.iter().sum()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     elves: Vec<Elf>,
 }
 
@@ -29,7 +51,7 @@ impl Input {
         self.elves
             .iter()
             .rev()
-            .take(3)
+            .take(TOP_COUNT)
             .map(|it| it.calories)
             .sum()
     }
@@ -40,23 +62,68 @@ struct Elf {
     calories: u64,
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let mut elves: Vec<Elf> = lines.split(is_empty!()).map(lines_to!(Elf)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let mut elves: Vec<Elf> = lines.split(is_empty!()).map(try_lines_to!(Elf)).collect::<ParseResult<_>>()?;
         elves.sort_by_key(|it| it.calories);
 
-        Self {
+        Ok(Self {
             elves
-        }
+        })
     }
 }
 
-impl FromLines for Elf {
-    fn from_lines(lines: &[&str]) -> Self {
-        let calories = lines.iter().map(line_to!(u64)).sum();
+impl TryFromLines for Elf {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let calories = lines.iter().map(try_line_to!(u64)).sum::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             calories
+        })
+    }
+}
+
+// Calories of the top elves, most first. Other elves are never needed, so they are not kept.
+#[derive(Debug)]
+pub struct TopElves {
+    top: [u64; TOP_COUNT],
+}
+
+// Top elves counted so far, and calories of the elf being counted.
+#[derive(Debug, Default)]
+pub struct Inventory {
+    top: [u64; TOP_COUNT],
+    calories: u64,
+}
+
+impl Inventory {
+    fn push_elf(&mut self) {
+        let calories = mem::take(&mut self.calories);
+        if let Some(index) = self.top.iter().position(|it| calories > *it) {
+            self.top.copy_within(index..TOP_COUNT - 1, index + 1);
+            self.top[index] = calories;
         }
     }
-}
\ No newline at end of file
+}
+
+impl TryFromLineStream for TopElves {
+    type State = Inventory;
+
+    fn try_push_line(inventory: &mut Inventory, line: &str) -> ParseResult<()> {
+        // Elves are separated by an empty line.
+        if line.is_empty() {
+            inventory.push_elf();
+        } else {
+            inventory.calories += u64::try_from_line(line)?;
+        }
+        Ok(())
+    }
+
+    fn try_finish(mut inventory: Inventory) -> ParseResult<Self> {
+        inventory.push_elf();
+
+        Ok(Self {
+            top: inventory.top
+        })
+    }
+}
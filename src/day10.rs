@@ -1,38 +1,144 @@
-use util::{FromLine, FromLines, read, run};
+use std::io;
 
-mod util;
+use crate::util::{Grid, ParseError, ParseResult, Solution, TryFromLine, TryFromLines, Vec2};
+use crate::util::parameters;
+use crate::util::stream::{StreamSolution, TryFromLineStream};
+use crate::util::viz::{Frame, Renderer};
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day10.txt"));
-    let (t1, (p1, p2)) = run(|| input.execute());
+parameters! {
+    pub struct Parameters {
+        #[valid(1..)]
+        crt_width: usize = 40,
+        #[valid(1..)]
+        crt_height: usize = 6,
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Input;
+    type Part1 = i64;
+    type Part2 = String;
+
+    fn part_1(input: &Input) -> i64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> String {
+        input.part_2()
+    }
+
+    fn animate(input: &Input, renderer: &mut Renderer) -> io::Result<()> {
+        input.animate(renderer)
+    }
 
-    println!("Part 1 :\n{}", p1);
-    println!("Part 2 :\n{}", p2);
-    println!("Time : {} ns", (t0 + t1).as_nanos());
+    fn parameters(input: &mut Input) -> Option<&mut dyn parameters::Parameters> {
+        Some(&mut input.parameters)
+    }
+}
+
+impl StreamSolution for Day10 {
+    type Stream = Output;
+
+    fn stream_part_1(stream: &Output) -> i64 {
+        stream.signal
+    }
+
+    fn stream_part_2(stream: &Output) -> String {
+        stream.screen.to_string()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     program: Program,
+    parameters: Parameters,
 }
 
 impl Input {
-    fn execute(&self) -> (i64, String) {
-        let mut cpu = Cpu::new();
-        let mut crt = Crt::<40, 6>::new();
-        let mut process = Process::new(&self.program);
+    fn part_1(&self) -> i64 {
+        self.execute(|_| {}).signal
+    }
+
+    fn part_2(&self) -> String {
+        self.execute(|_| {}).screen.to_string()
+    }
+
+    // Screen being drawn, cycle by cycle. Stops at the first error.
+    fn animate(&self, renderer: &mut Renderer) -> io::Result<()> {
+        let mut result = Ok(());
+        self.execute(|crt| {
+            if result.is_ok() { result = renderer.render(crt); }
+        });
+        result
+    }
+
+    fn execute<F>(&self, mut on_cycle: F) -> Output
+        where F: FnMut(&Crt) {
+        let mut device = Device::new(self.parameters.clone());
+        for instruction in &self.program {
+            device.execute(instruction, &mut on_cycle);
+        }
+        device.finish()
+    }
+}
+
+// What is left once the program ran : the signal strength, and the last screen drawn. Streamed
+// programs only keep this.
+#[derive(Debug)]
+pub struct Output {
+    signal: i64,
+    screen: Grid<char>,
+}
+
+// Runs a program one instruction at a time, so instructions don't have to be kept.
+#[derive(Debug)]
+pub struct Device {
+    parameters: Parameters,
+    cpu: Cpu,
+    // Created with the first instruction, once the parameters are set.
+    crt: Option<Crt>,
+    cycle: i64,
+    signal: i64,
+}
+
+impl Device {
+    fn new(parameters: Parameters) -> Self {
+        Self {
+            parameters,
+            cpu: Cpu::new(),
+            crt: None,
+            cycle: 0,
+            signal: 0,
+        }
+    }
 
-        let mut signal = 0;
-        let mut cycle = 1;
+    fn execute<F>(&mut self, instruction: &Instruction, mut on_cycle: F)
+        where F: FnMut(&Crt) {
+        let crt = self.crt.get_or_insert_with(|| Crt::new(self.parameters.crt_width, self.parameters.crt_height));
         loop {
-            if (cycle) % 40 == 20 { signal += cycle * cpu.x; }
-            cycle += 1;
+            self.cycle += 1;
+            if self.cycle % 40 == 20 { self.signal += self.cycle * self.cpu.x; }
 
-            crt.tick(&cpu);
-            if !process.tick(&mut cpu) { break; }; // Update Cpu last to end a cycle.
+            crt.tick(&self.cpu);
+            on_cycle(crt);
+            if !self.cpu.tick(instruction) { break; } // Update Cpu last to end a cycle.
         }
+    }
 
-        (signal, crt.screen.iter().map(|it| it.iter().collect::<String>()).collect::<Vec<String>>().join("\n"))
+    fn finish(self) -> Output {
+        let crt = self.crt.unwrap_or_else(|| Crt::new(self.parameters.crt_width, self.parameters.crt_height));
+        Output {
+            signal: self.signal,
+            screen: crt.screen,
+        }
+    }
+}
+
+impl Default for Device {
+    fn default() -> Self {
+        Self::new(Parameters::default())
     }
 }
 
@@ -53,7 +159,7 @@ impl Cpu {
     }
 
     fn tick(&mut self, instruction: &Instruction) -> bool {
-        self.cs = self.cs.or_else(|| {
+        self.cs = self.cs.or({
             // No current instruction. Set it.
             match instruction {
                 Instruction::Noop => Some((*instruction, 0)),
@@ -83,41 +189,17 @@ impl Cpu {
 }
 
 #[derive(Debug)]
-struct Process<'a> {
-    program: &'a Program,
-    program_counter: usize,
-}
-
-impl<'a> Process<'a> {
-    fn new(program: &'a Program) -> Self {
-        Self {
-            program,
-            program_counter: 0,
-        }
-    }
-
-    fn tick(&mut self, cpu: &mut Cpu) -> bool {
-        if self.program_counter < self.program.len() {
-            if !cpu.tick(&self.program[self.program_counter]) {
-                self.program_counter += 1;
-            }
-        }
-        self.program_counter < self.program.len()
-    }
-}
-
-#[derive(Debug)]
-struct Crt<const W: usize, const H: usize> {
+struct Crt {
     // Screen raster.
-    screen: [[char; W]; H],
+    screen: Grid<char>,
     // Position in the raster.
     position: usize,
 }
 
-impl<const W: usize, const H: usize> Crt<W, H> {
-    fn new() -> Self {
+impl Crt {
+    fn new(width: usize, height: usize) -> Self {
         Self {
-            screen: [['░'; W]; H],
+            screen: Grid::new(width, height, '░'),
             position: 0,
         }
     }
@@ -126,17 +208,30 @@ impl<const W: usize, const H: usize> Crt<W, H> {
         const SPRITE_LEN: i64 = 3;
         const SPRITE_OFFSET: i64 = SPRITE_LEN / 2;
 
-        let (ray_x, ray_y) = (self.position % W, self.position / W);
+        let width = self.screen.width();
+        let (ray_x, ray_y) = (self.position % width, self.position / width);
 
-        let sprite_x = cpu.x as i64;
+        let sprite_x = cpu.x;
         let sprite_range = sprite_x - SPRITE_OFFSET..=sprite_x + SPRITE_OFFSET;
-        self.screen[ray_y][ray_x] = if sprite_range.contains(&(ray_x as i64)) {
+        self.screen[vec2!(ray_x, ray_y)] = if sprite_range.contains(&(ray_x as i64)) {
             '▓'
         } else {
             '░'
         };
 
-        self.position += 1;
+        // The ray goes back to the top once the screen is drawn.
+        self.position = (self.position + 1) % (width * self.screen.height());
+    }
+}
+
+impl Frame for Crt {
+    fn bounds(&self) -> (Vec2<isize>, Vec2<isize>) {
+        (vec2!(0, 0), vec2!(self.screen.width() as isize - 1, self.screen.height() as isize - 1))
+    }
+
+    fn cell(&self, position: Vec2<isize>) -> char {
+        let position = Vec2::<usize>::try_from(position).ok();
+        position.and_then(|it| self.screen.get(it)).copied().unwrap_or(' ')
     }
 }
 
@@ -148,23 +243,41 @@ enum Instruction {
     AddX(i64),
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let program = lines.iter().map(line_to!(Instruction)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let program = lines.iter().map(try_line_to!(Instruction)).collect::<ParseResult<_>>()?;
 
-        Self {
-            program
-        }
+        Ok(Self {
+            program,
+            parameters: Parameters::default(),
+        })
+    }
+}
+
+impl TryFromLineStream for Output {
+    type State = Device;
+
+    fn try_push_line(device: &mut Device, line: &str) -> ParseResult<()> {
+        device.execute(&Instruction::try_from_line(line)?, |_| {});
+        Ok(())
+    }
+
+    fn try_finish(device: Device) -> ParseResult<Self> {
+        Ok(device.finish())
+    }
+
+    fn parameters(device: &mut Device) -> Option<&mut dyn parameters::Parameters> {
+        Some(&mut device.parameters)
     }
 }
 
-impl FromLine for Instruction {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for Instruction {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         let parts: Vec<&str> = line.split(' ').collect();
         match parts[..] {
-            ["noop"] => Self::Noop,
-            ["addx", value] => Self::AddX(i64::from_line(value)),
-            _ => panic!("{line} is not a valid instruction")
+            ["noop"] => Ok(Self::Noop),
+            ["addx", value] => Ok(Self::AddX(i64::try_from_line(value)?)),
+            _ => Err(ParseError::new(line, "instruction is not valid"))
         }
     }
 }
\ No newline at end of file
@@ -1,28 +1,50 @@
 use std::mem;
 
-use util::{FromLine, FromLines, read, run};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
+use crate::util::parameters;
+
+parameters! {
+    pub struct Parameters {
+        #[valid(1..)]
+        part_1_rounds: usize = 20,
+        #[valid(1..)]
+        part_1_relief: u64 = 3,
+        #[valid(1..)]
+        part_2_rounds: usize = 10000,
+    }
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
 
-mod util;
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day11.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn parameters(input: &mut Input) -> Option<&mut dyn parameters::Parameters> {
+        Some(&mut input.parameters)
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     monkeys: Vec<Monkey>,
+    parameters: Parameters,
 }
 
 impl Input {
     fn part_1(&self) -> usize {
         // 20 rounds. Worry is divided by 3 before monkey inspection.
-        Self::monkey_business(self.monkeys.clone(), 20, |it| it / 3)
+        let relief = self.parameters.part_1_relief;
+        Self::monkey_business(self.monkeys.clone(), self.parameters.part_1_rounds, |it| it / relief)
     }
 
     fn part_2(&self) -> usize {
@@ -124,7 +146,7 @@ impl Input {
         // We have successfully managed our overflows! Now, here's the code!
         let modulo: u64 = self.monkeys.iter().map(|it| it.test.divisible_by).product();
 
-        Self::monkey_business(self.monkeys.clone(), 10000, |it| it % modulo)
+        Self::monkey_business(self.monkeys.clone(), self.parameters.part_2_rounds, |it| it % modulo)
     }
 
     fn monkey_business<F>(mut monkeys: Vec<Monkey>, iterations: usize, worry: F) -> usize
@@ -173,7 +195,7 @@ struct Items {
 
 impl Items {
     fn take(&mut self) -> Vec<u64> {
-        mem::replace(&mut self.items, Vec::new())
+        mem::take(&mut self.items)
     }
 }
 
@@ -203,88 +225,76 @@ struct Test {
 
 impl Test {
     fn apply(&self, value: u64) -> bool {
-        value % self.divisible_by == 0
+        value.is_multiple_of(self.divisible_by)
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let monkeys = lines.split(is_empty!()).map(lines_to!(Monkey)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let monkeys = lines.split(is_empty!()).map(try_lines_to!(Monkey)).collect::<ParseResult<_>>()?;
 
-        Self {
-            monkeys
-        }
+        Ok(Self {
+            monkeys,
+            parameters: Parameters::default(),
+        })
     }
 }
 
-impl FromLines for Monkey {
-    fn from_lines(lines: &[&str]) -> Self {
-        if lines.len() != 6 { panic!("monkey should have 6 lines describing it"); }
+impl TryFromLines for Monkey {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        if lines.len() != 6 { return Err(ParseError::new(lines.first().unwrap_or(&""), "monkey should have 6 lines describing it")); }
 
-        let items = Items::from_line(&lines[1]);
-        let operation = Operation::from_line(&lines[2]);
-        let test = Test::from_lines(&lines[3..]);
+        let (items,) = parse!(lines[1], "Starting items: {}", Items)?;
+        let (operation,) = parse!(lines[2], "Operation: new = old {}", Operation)?;
+        let test = Test::try_from_lines(&lines[3..])?;
 
-        Self {
+        Ok(Self {
             items,
             operation,
             test,
             inspection_count: 0,
-        }
+        })
     }
 }
 
-impl FromLine for Items {
-    fn from_line(line: &str) -> Self {
-        if line.len() < 18 { panic!("{line} is not a valid starting item list"); }
-
-        let items = line[18..]
+impl TryFromLine for Items {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let items = line
             .split(',')
-            .map(|it| u64::from_line(it.trim()))
-            .collect();
+            .map(|it| u64::try_from_line(it.trim()))
+            .collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             items
-        }
+        })
     }
 }
 
-impl FromLine for Operation {
-    fn from_line(line: &str) -> Self {
-        if line.len() < 25 { panic!("{line} is not a valid operation"); }
-
-        let operator = &line[23..24];
-        let value = &line[25..];
+impl TryFromLine for Operation {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let (operator, value) = line.split_once(' ').ok_or_else(|| ParseError::new(line, "operation should have an operator and a value"))?;
 
-        match (operator, value) {
-            ("*", "old") => Self::Pow,
-            ("*", value) => Self::Multiply(u64::from_line(value)),
-            ("+", value) => Self::Add(u64::from_line(value)),
-            _ => panic!("{line} is not a valid operation")
+        match (operator, value.trim()) {
+            ("*", "old") => Ok(Self::Pow),
+            ("*", value) => Ok(Self::Multiply(u64::try_from_line(value)?)),
+            ("+", value) => Ok(Self::Add(u64::try_from_line(value)?)),
+            _ => Err(ParseError::new(operator, "operation is not valid"))
         }
     }
 }
 
-impl FromLines for Test {
-    fn from_lines(lines: &[&str]) -> Self {
-        if lines.len() != 3 { panic!("test should have 3 lines describing it"); }
+impl TryFromLines for Test {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        if lines.len() != 3 { return Err(ParseError::new(lines.first().unwrap_or(&""), "test should have 3 lines describing it")); }
 
-        let divisible_by = &lines[0];
-        if divisible_by.len() < 21 { panic!("{divisible_by} is not a valid test division statement"); }
-        let divisible_by = u64::from_line(&divisible_by[21..]);
+        let (divisible_by,) = parse!(lines[0], "Test: divisible by {}", u64)?;
+        let (true_throw_to,) = parse!(lines[1], "If true: throw to monkey {}", usize)?;
+        let (false_throw_to,) = parse!(lines[2], "If false: throw to monkey {}", usize)?;
 
-        let true_throw_to = &lines[1];
-        if true_throw_to.len() < 29 { panic!("{true_throw_to} is not a valid test true statement"); }
-        let true_throw_to = usize::from_line(&true_throw_to[29..]);
-
-        let false_throw_to = &lines[2];
-        if false_throw_to.len() < 30 { panic!("{false_throw_to} is not a valid test false statement"); }
-        let false_throw_to = usize::from_line(&false_throw_to[30..]);
-
-        Self {
+        Ok(Self {
             divisible_by,
             true_throw_to,
             false_throw_to,
-        }
+        })
     }
-}
\ No newline at end of file
+}
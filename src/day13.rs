@@ -1,21 +1,25 @@
 use std::cmp::Ordering;
 
-use util::{FromLine, FromLines, read, run};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
 
-mod util;
+pub struct Day13;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day13.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+impl Solution for Day13 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     packets: Vec<Packet>,
 }
 
@@ -67,39 +71,41 @@ impl PartialOrd for Packet {
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let packets = lines.iter().filter(is_not_empty!()).map(line_to!(Packet)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let packets = lines.iter().filter(is_not_empty!()).map(try_line_to!(Packet)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             packets
-        }
+        })
     }
 }
 
-impl FromLine for Packet {
-    fn from_line(line: &str) -> Self {
-        if line.len() < 1 { panic!("packet should not be empty"); }
+impl TryFromLine for Packet {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        if line.is_empty() { return Err(ParseError::new(line, "packet should not be empty")); }
 
         match &line[0..1] {
             "[" => {
+                if line.len() < 2 || !line.ends_with(']') { return Err(ParseError::new(line, "packet list should end with a ]")); }
+
                 let mut depth = 0;
 
-                Self::List(
+                Ok(Self::List(
                     line[1..line.len() - 1]
                         .split(|it| {
                             if it == '[' { depth += 1 } else if it == ']' { depth -= 1 };
                             it == ',' && depth == 0
                         })
                         .filter(is_not_empty!())
-                        .map(line_to!(Packet))
-                        .collect()
-                )
+                        .map(try_line_to!(Packet))
+                        .collect::<ParseResult<_>>()?
+                ))
             }
             _ => {
-                Self::Number(
-                    u64::from_line(line)
-                )
+                Ok(Self::Number(
+                    u64::try_from_line(line)?
+                ))
             }
         }
     }
@@ -1,9 +1,9 @@
-use std::fmt;
+use std::io;
 use std::iter::successors;
 
-use util::{FromLine, FromLines, read, run, Vec2};
-
-mod util;
+use crate::util::{Grid, ParseError, ParseResult, Solution, TryFromLine, TryFromLines, Vec2};
+use crate::util::image::{Exporter, Pixmap, Rgb};
+use crate::util::viz::{Frame, Renderer};
 
 // Optimisation opportunity : the grid is way too big for the problem. It's possible to shrink it.
 // The required height is equal to the maximum rock Y position. The required width is equal
@@ -14,60 +14,120 @@ const SAND_SOURCE: Position = vec2!(500, 0);
 const SIMULATION_WIDTH: usize = SAND_SOURCE.x() * 2 + 1;
 const SIMULATION_HEIGHT: usize = SIMULATION_WIDTH;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day14.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+// Each position is drawn as a square of this size.
+const EXPORT_SCALE: usize = 4;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
+
+    fn animate(input: &Input, renderer: &mut Renderer) -> io::Result<()> {
+        input.animate(renderer)
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn export(input: &Input, exporter: &mut Exporter) -> io::Result<()> {
+        input.export(exporter)
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     rock_formations: Vec<RockFormation>,
 }
 
 impl Input {
     fn part_1(&self) -> usize {
-        let mut simulation = Simulation::<SIMULATION_WIDTH, SIMULATION_HEIGHT>::new();
-
-        // Add rock formations
-        for formation in &self.rock_formations {
-            for position in formation.positions() {
-                *simulation.cell_mut(&position) = Cell::Rock;
-            }
-        }
+        let mut simulation = self.simulation();
 
         // Run simulation
-        simulation.flow(&SAND_SOURCE)
+        simulation.flow(&SAND_SOURCE, |_| {})
+    }
+
+    // Sand of the first part, one block at a time. Stops at the first error.
+    fn animate(&self, renderer: &mut Renderer) -> io::Result<()> {
+        let mut simulation = self.simulation();
+
+        let (min, max) = self.bounds();
+        let to_frame_position = |it: Position| Vec2::<isize>::try_from(it).expect("simulation position should fit in a frame position");
+        let bounds = (to_frame_position(min), to_frame_position(max));
+
+        let mut result = Ok(());
+        simulation.flow(&SAND_SOURCE, |simulation| {
+            if result.is_ok() { result = renderer.render(&SimulationFrame { simulation, bounds }); }
+        });
+        result
     }
 
     fn part_2(&self) -> usize {
-        let mut simulation = Simulation::<SIMULATION_WIDTH, SIMULATION_HEIGHT>::new();
+        let mut simulation = self.floored_simulation();
+
+        // Run simulation
+        simulation.fill(&SAND_SOURCE)
+    }
+
+    // Sand once settled, for both parts.
+    fn export(&self, exporter: &mut Exporter) -> io::Result<()> {
+        let mut simulation = self.simulation();
+        simulation.flow(&SAND_SOURCE, |_| {});
+        let (min, max) = self.bounds();
+        exporter.save("part_1", &simulation.image(min, max).scale(EXPORT_SCALE))?;
+
+        // Sand ends up in a pyramid, as wide as it is high.
+        let mut simulation = self.floored_simulation();
+        simulation.fill(&SAND_SOURCE);
+        let floor_height = self.floor_height();
+        let min = vec2!(SAND_SOURCE.x().saturating_sub(floor_height + 1), 0);
+        let max = vec2!(SAND_SOURCE.x() + floor_height + 1, floor_height);
+        exporter.save("part_2", &simulation.image(min, max).scale(EXPORT_SCALE))
+    }
+
+    // Only the rocks and the source are worth drawing. Sand settles between them.
+    fn bounds(&self) -> (Position, Position) {
+        let (min, max) = self.rock_formations
+            .iter()
+            .flat_map(|it| it.positions())
+            .fold((SAND_SOURCE, SAND_SOURCE), |(min, max), it| {
+                (vec2!(min.x().min(it.x()), min.y().min(it.y())), vec2!(max.x().max(it.x()), max.y().max(it.y())))
+            });
+
+        // Sand can settle on the sides of the rocks.
+        (vec2!(min.x().saturating_sub(1), min.y()), vec2!(max.x() + 1, max.y()))
+    }
+
+    fn floor_height(&self) -> usize {
+        self.rock_formations.iter().map(|it| it.max_height()).max().unwrap_or(0) + 2
+    }
 
-        // Add rock formations
+    // Empty simulation, with the rock formations.
+    fn simulation(&self) -> Simulation {
+        let mut simulation = Simulation::new(SIMULATION_WIDTH, SIMULATION_HEIGHT);
         for formation in &self.rock_formations {
             for position in formation.positions() {
                 *simulation.cell_mut(&position) = Cell::Rock;
             }
         }
+        simulation
+    }
 
-        // Add floor
-        let floor_height = self.rock_formations.iter().map(|it| it.max_height()).max().unwrap_or(0) + 2;
+    // Same, with the floor of the second part.
+    fn floored_simulation(&self) -> Simulation {
+        let mut simulation = self.simulation();
+        let floor_height = self.floor_height();
         for x in 0..SIMULATION_WIDTH {
             *simulation.cell_mut(&vec2!(x,floor_height)) = Cell::Rock;
         }
-
-        // Run simulation
-        simulation.fill(&SAND_SOURCE)
-    }
-
-    // Export the simulation to a file.
-    #[allow(unused)]
-    fn export_simulation<const W: usize, const H: usize>(path: &str, simulation: &Simulation<W, H>) {
-        std::fs::write(path, format!("{}", &simulation)).unwrap();
+        simulation
     }
 }
 
@@ -75,30 +135,28 @@ type Position = Vec2<usize>;
 type Direction = Vec2<isize>;
 
 #[derive(Debug)]
-struct Simulation<const W: usize, const H: usize> {
-    grid: [[Cell; W]; H],
+struct Simulation {
+    grid: Grid<Cell>,
 }
 
-impl<const W: usize, const H: usize> Simulation<W, H> {
-    fn new() -> Self {
+impl Simulation {
+    fn new(width: usize, height: usize) -> Self {
         Self {
-            grid: [[Cell::Empty; W]; H]
+            grid: Grid::new(width, height, Cell::Empty)
         }
     }
 
     fn cell(&self, position: &Position) -> &Cell {
-        &self.grid[position.y()][position.x()]
+        &self.grid[*position]
     }
 
     fn cell_mut(&mut self, position: &Position) -> &mut Cell {
-        &mut self.grid[position.y()][position.x()]
-    }
-
-    fn is_in_bounds(&self, position: &Position) -> bool {
-        position.x() < W && position.y() < H
+        &mut self.grid[*position]
     }
 
-    fn flow(&mut self, start: &Position) -> usize {
+    // Calls on_settle each time a sand block settles.
+    fn flow<F>(&mut self, start: &Position, mut on_settle: F) -> usize
+        where F: FnMut(&Self) {
         // Count of sand blocks added.
         let mut count = 0;
 
@@ -116,7 +174,7 @@ impl<const W: usize, const H: usize> Simulation<W, H> {
                 // Fall.
                 'direction: for direction in &DIRECTIONS {
                     let next_pos = current_pos + *direction;
-                    let next_cell = next_pos.filter(|it| self.is_in_bounds(it)).map(|it| self.cell(&it));
+                    let next_cell = next_pos.and_then(|it| self.grid.get(it));
 
                     match next_cell {
                         // Empty. Fall.
@@ -138,6 +196,7 @@ impl<const W: usize, const H: usize> Simulation<W, H> {
                 // Can't fall anymore. Settle.
                 *self.cell_mut(&current_pos) = Cell::Sand;
                 count += 1;
+                on_settle(self);
                 continue 'flow;
             }
         }
@@ -146,15 +205,14 @@ impl<const W: usize, const H: usize> Simulation<W, H> {
 
     fn fill(&mut self, start: &Position) -> usize {
         // Put the first sand block.
-        *self.cell_mut(&start) = Cell::Sand;
+        *self.cell_mut(start) = Cell::Sand;
 
         // Count of sand blocks added. First one is already done.
         let mut count = 1;
 
         // Make the sand flow, line by line.
-        // Start at the line where the starting sand block is.
-        let mut depth = 0;
-        for y in start.y()..H - 1 {
+        // Start at the line where the starting sand block is. Depth increases with each line.
+        for (depth, y) in (start.y()..self.grid.height() - 1).enumerate() {
             // Last count.
             let last_count = count;
 
@@ -175,28 +233,38 @@ impl<const W: usize, const H: usize> Simulation<W, H> {
 
             // Abort if no sand block was added.
             if last_count == count { break; }
-
-            // Increase depth.
-            depth += 1;
         }
         count
     }
+
+    // Part of the simulation between two corners (inclusive). The source is drawn in red.
+    fn image(&self, min: Position, max: Position) -> Pixmap {
+        let mut pixels = Grid::new(max.x() - min.x() + 1, max.y() - min.y() + 1, Rgb::BLACK);
+        for position in pixels.positions().collect::<Vec<_>>() {
+            let cell = position + min;
+            pixels[position] = if cell == SAND_SOURCE { Rgb::RED } else { self.grid.get(cell).copied().unwrap_or(Cell::Empty).color() };
+        }
+        Pixmap::new(pixels)
+    }
 }
 
-impl<const W: usize, const H: usize> fmt::Display for Simulation<W, H> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..W {
-            for x in 0..H {
-                let position = vec2!(x, y);
-                if position == SAND_SOURCE {
-                    write!(f, "+")?;
-                } else {
-                    write!(f, "{}", self.cell(&position))?;
-                }
-            }
-            writeln!(f)?;
+// Part of a simulation, drawn in a terminal.
+struct SimulationFrame<'a> {
+    simulation: &'a Simulation,
+    bounds: (Vec2<isize>, Vec2<isize>),
+}
+
+impl Frame for SimulationFrame<'_> {
+    fn bounds(&self) -> (Vec2<isize>, Vec2<isize>) {
+        self.bounds
+    }
+
+    fn cell(&self, position: Vec2<isize>) -> char {
+        match Position::try_from(position).ok() {
+            Some(SAND_SOURCE) => '+',
+            Some(position) => self.simulation.grid.get(position).copied().unwrap_or(Cell::Empty).char(),
+            None => Cell::Empty.char(),
         }
-        Ok(())
     }
 }
 
@@ -207,12 +275,20 @@ enum Cell {
     Sand,
 }
 
-impl fmt::Display for Cell {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self {
-            Cell::Empty => write!(f, "."),
-            Cell::Sand => write!(f, "o"),
-            Cell::Rock => write!(f, "#"),
+impl Cell {
+    fn char(&self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Rock => '#',
+            Cell::Sand => 'o',
+        }
+    }
+
+    fn color(&self) -> Rgb {
+        match self {
+            Cell::Empty => Rgb::BLACK,
+            Cell::Rock => Rgb::GRAY,
+            Cell::Sand => Rgb::YELLOW,
         }
     }
 }
@@ -222,12 +298,9 @@ struct RockFormation(Vec<Position>);
 
 impl RockFormation {
     fn positions(&self) -> impl Iterator<Item=Position> + '_ {
-        self.0.iter().zip(self.0[1..].iter()).map(|(start, end)| {
-            let direction = {
-                let x = if start.x() < end.x() { 1isize } else if start.x() > end.x() { -1isize } else { 0 };
-                let y = if start.y() < end.y() { 1isize } else if start.y() > end.y() { -1isize } else { 0 };
-                vec2!(x, y)
-            };
+        self.0.iter().zip(self.0[1..].iter()).flat_map(|(start, end)| {
+            let to_direction = |it: Position| Direction::try_from(it).expect("rock position should fit in a direction");
+            let direction = (to_direction(*end) - to_direction(*start)).signum();
 
             successors(Some(*start), move |position| {
                 if position != end {
@@ -236,7 +309,7 @@ impl RockFormation {
                     None
                 }
             })
-        }).flatten()
+        })
     }
 
     fn max_height(&self) -> usize {
@@ -244,21 +317,21 @@ impl RockFormation {
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let rock_formations = lines.iter().map(line_to!(RockFormation)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let rock_formations = lines.iter().map(try_line_to!(RockFormation)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             rock_formations
-        }
+        })
     }
 }
 
-impl FromLine for RockFormation {
-    fn from_line(line: &str) -> Self {
-        let points: Vec<Position> = line.split(" -> ").map(line_to!(Vec2<usize>)).collect();
-        if points.len() < 2 { panic!("rock formation should have at least two points"); }
+impl TryFromLine for RockFormation {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let points: Vec<Position> = line.split(" -> ").map(try_line_to!(Vec2<usize>)).collect::<ParseResult<_>>()?;
+        if points.len() < 2 { return Err(ParseError::new(line, "rock formation should have at least two points")); }
 
-        Self(points)
+        Ok(Self(points))
     }
 }
@@ -1,46 +1,67 @@
+use std::io;
 use std::thread;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 
-use util::{FromLine, FromLines, read, run, Vec2};
-
-mod util;
+use crate::util::{ParseResult, Solution, TryFromLine, TryFromLines, Vec2};
+use crate::util::image::{Exporter, Rgb, Style, Svg};
+use crate::util::interval::{Interval, IntervalSet};
+use crate::util::parameters;
 
 // Optimisation opportunity : find all intersections of the sensors (it's diamond shaped).
 // For each of them, check if the position just bellow is inside one of the sensors ranges.
 // If not, it's the positions we are looking for. For now, this "brute force" solution will do.
 
-const PART_1_HEIGHT: i64 = 2_000_000;
-// const PART_1_HEIGHT: i64 = 10;
-const PART_2_HEIGHT: i64 = 4_000_000;
-// const PART_2_HEIGHT: i64 = 20;
-const PART_2_MULTIPLIER: i64 = 4_000_000;
+parameters! {
+    pub struct Parameters {
+        part_1_height: i64 = 2_000_000,
+        #[valid(0..)]
+        part_2_height: i64 = 4_000_000,
+        part_2_multiplier: i64 = 4_000_000,
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Input;
+    type Part1 = u128;
+    type Part2 = i64;
+
+    fn part_1(input: &Input) -> u128 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> i64 {
+        input.part_2()
+    }
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day15.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+    fn parameters(input: &mut Input) -> Option<&mut dyn parameters::Parameters> {
+        Some(&mut input.parameters)
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn export(input: &Input, exporter: &mut Exporter) -> io::Result<()> {
+        input.export(exporter)
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     report: Report,
+    parameters: Parameters,
 }
 
 impl Input {
-    fn part_1(&self) -> i64 {
-        // Extract intervals.
-        let intervals = self.report.slice(PART_1_HEIGHT);
+    fn part_1(&self) -> u128 {
+        // Positions covered by the sensors.
+        let covered = self.report.slice(self.parameters.part_1_height);
 
-        // Merge intervals.
-        let intervals = Report::merge(intervals);
+        // Positions where a beacon is. Many sensors can have the same closest beacon.
+        let beacons = self.report.beacons_at(self.parameters.part_1_height);
+        let beacons: IntervalSet<i64> = beacons.map(|it| Interval::new(it, it)).collect();
 
-        // Counts positions covered by interval.
-        Report::length(&intervals)
+        // Counts positions covered, where there can't be a beacon.
+        covered.difference(&beacons).length()
     }
 
     fn part_2(&self) -> i64 {
@@ -53,33 +74,31 @@ impl Input {
 
         // Create threads.
         thread::scope(|s| {
-            let thread_height = (PART_2_HEIGHT as usize / thread_count) as i64;
+            // Rows go from 0 to height (inclusive). Round up, so the last rows are not left out.
+            let thread_height = (self.parameters.part_2_height + thread_count as i64) / thread_count as i64;
             for i in 0..thread_count {
                 let found = found.clone();
                 let value = value.clone();
                 s.spawn(move || {
                     let start = i as i64 * thread_height;
-                    let end = start + thread_height;
+                    let end = (start + thread_height).min(self.parameters.part_2_height + 1);
 
                     for y in start..end {
                         // Has a thread found it yet ?
                         if found.load(Ordering::Relaxed) { break; }
 
-                        // Extract intervals.
-                        let intervals = self.report.slice(y);
-                        // Merge intervals.
-                        let intervals = Report::merge(intervals);
+                        // Positions not covered by the sensors.
+                        let holes = self.report.slice(y).complement(Interval::new(0, self.parameters.part_2_height));
 
-                        // If there is a hole, there will be two intervals after merging the others.
-                        if intervals.len() >= 2 {
+                        // If there is a hole, there will be an interval left.
+                        if let Some(hole) = holes.intervals().first() {
                             // We found the hole. Stop everything!
                             found.store(true, Ordering::Relaxed);
 
-                            // The hole is at the end of the first interval.
-                            let x = intervals[0].end + 1;
+                            let x = hole.start();
 
                             // Submit the result.
-                            value.store(x * PART_2_MULTIPLIER + y, Ordering::Relaxed);
+                            value.store(x * self.parameters.part_2_multiplier + y, Ordering::Relaxed);
                             break;
                         }
                     }
@@ -89,6 +108,43 @@ impl Input {
 
         value.load(Ordering::Relaxed)
     }
+
+    // Coverage of the sensors, with the row of the first part and the area of the second part.
+    fn export(&self, exporter: &mut Exporter) -> io::Result<()> {
+        // Everything covered by a sensor, and the area of the second part.
+        let area = self.parameters.part_2_height;
+        let (min, max) = self.report.sensors.iter().fold((vec2!(0, 0), vec2!(area, area)), |(min, max), it| {
+            let (x, y, distance) = (it.position.x(), it.position.y(), it.distance);
+            (vec2!(min.x().min(x - distance), min.y().min(y - distance)), vec2!(max.x().max(x + distance), max.y().max(y + distance)))
+        });
+        let (width, height) = ((max.x() - min.x()) as f64, (max.y() - min.y()) as f64);
+
+        // Lines are as thick as a thousandth of the drawing, whatever the scale of the input.
+        let thickness = width.max(height) / 1000.0;
+
+        let mut svg = Svg::new(min.x() as f64, min.y() as f64, width, height);
+        svg.rect(min.x() as f64, min.y() as f64, width, height, Style::fill(Rgb::WHITE));
+        for sensor in &self.report.sensors {
+            let (x, y, distance) = (sensor.position.x() as f64, sensor.position.y() as f64, sensor.distance as f64);
+            let diamond = [(x, y - distance), (x + distance, y), (x, y + distance), (x - distance, y)];
+            svg.polygon(&diamond, Style::fill(Rgb::BLUE).opacity(0.3));
+        }
+
+        let area = area as f64;
+        svg.rect(0.0, 0.0, area, area, Style::stroke(Rgb::GREEN, thickness * 2.0));
+        let row = self.parameters.part_1_height as f64;
+        svg.line((min.x() as f64, row), (max.x() as f64, row), Style::stroke(Rgb::RED, thickness * 2.0));
+
+        for sensor in &self.report.sensors {
+            let (x, y) = (sensor.position.x() as f64, sensor.position.y() as f64);
+            let (beacon_x, beacon_y) = (sensor.beacon.x() as f64, sensor.beacon.y() as f64);
+            svg.line((x, y), (beacon_x, beacon_y), Style::stroke(Rgb::BLACK, thickness));
+            svg.circle(x, y, thickness * 4.0, Style::fill(Rgb::BLACK));
+            svg.circle(beacon_x, beacon_y, thickness * 4.0, Style::fill(Rgb::YELLOW));
+        }
+
+        exporter.save("coverage", &svg)
+    }
 }
 
 type Position = Vec2<i64>;
@@ -99,7 +155,7 @@ struct Report {
 }
 
 impl Report {
-    fn slice(&self, height: i64) -> Vec<Interval> {
+    fn slice(&self, height: i64) -> IntervalSet<i64> {
         self.sensors
             .iter()
             .filter_map(|sensor| {
@@ -119,130 +175,53 @@ impl Report {
             .collect()
     }
 
-    fn merge(mut intervals: Vec<Interval>) -> Vec<Interval> {
-        // Sort by start value.
-        intervals.sort_by_key(|it| it.start);
-
-        // Fold intervals into each other, starting at the first one.
-        let mut current = 0;
-        for other in 1..intervals.len() {
-            let other_interval = intervals[other];
-            let current_interval = &mut intervals[current];
-
-            if current_interval.overlap_end(&other_interval) {
-                current_interval.merge_end(&other_interval);
-            } else {
-                current += 1;
-                intervals[current] = other_interval;
-            }
-        }
-
-        // Remove intervals after current. They were all merged.
-        intervals.resize_with(intervals.len().min(current + 1), || panic!("new size should be smaller or equal after merging"));
-        intervals
-    }
-
-    fn length(slices: &Vec<Interval>) -> i64 {
-        // Sum of the lengths is equal to the total length covered.
-        slices.iter().map(|it| it.len()).sum()
+    fn beacons_at(&self, height: i64) -> impl Iterator<Item=i64> + '_ {
+        self.sensors
+            .iter()
+            .map(|it| it.beacon)
+            .filter(move |it| it.y() == height)
+            .map(|it| it.x())
     }
 }
 
 #[derive(Debug)]
 struct Sensor {
     position: Position,
+    beacon: Position,
     distance: i64,
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-struct Interval {
-    start: i64,
-    end: i64,
-}
-
-impl Interval {
-    fn new(start: i64, end: i64) -> Self {
-        Self {
-            start,
-            end,
-        }
-    }
-
-    fn len(&self) -> i64 {
-        self.end - self.start
-    }
-
-    #[allow(unused)]
-    fn overlap(&self, other: &Self) -> bool {
-        self.overlap_start(&other) || self.overlap_end(&other)
-    }
-
-    #[allow(unused)]
-    fn overlap_start(&self, other: &Self) -> bool {
-        self.start <= other.end
-    }
-
-    fn overlap_end(&self, other: &Self) -> bool {
-        other.start <= self.end
-    }
-
-    #[allow(unused)]
-    fn merge(&mut self, other: &Self) {
-        self.merge_start(other);
-        self.merge_end(other);
-    }
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let report = Report::try_from_lines(lines)?;
 
-    #[allow(unused)]
-    fn merge_start(&mut self, other: &Self) {
-        self.start = self.start.min(other.start);
-    }
-
-    fn merge_end(&mut self, other: &Self) {
-        self.end = self.end.max(other.end);
-    }
-}
-
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let report = Report::from_lines(lines);
-
-        Self {
-            report
-        }
+        Ok(Self {
+            report,
+            parameters: Parameters::default(),
+        })
     }
 }
 
-impl FromLines for Report {
-    fn from_lines(lines: &[&str]) -> Self {
-        let sensors = lines.iter().map(line_to!(Sensor)).collect();
+impl TryFromLines for Report {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let sensors = lines.iter().map(try_line_to!(Sensor)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             sensors
-        }
+        })
     }
 }
 
-impl FromLine for Sensor {
-    fn from_line(line: &str) -> Self {
-        fn find_coordinate(line: &str, start_delimiter: char, end_delimiter: Option<char>) -> (&str, &str) {
-            let start_pos = line.find(start_delimiter).expect("delimiter should exist");
-            let end_pos = end_delimiter.map(|end_delimiter| line.find(end_delimiter).expect("delimiter should exist")).unwrap_or(line.len());
-            let coordinate = &line[start_pos + 1..end_pos];
-            (coordinate, &line[end_pos..])
-        }
-
-        let (x, line) = find_coordinate(line, '=', Some(','));
-        let (y, line) = find_coordinate(line, '=', Some(':'));
-        let sensor = vec2!(i64::from_line(x), i64::from_line(y));
+impl TryFromLine for Sensor {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        // Positions are "x=..., y=...".
+        let (sensor, beacon) = parse!(line, "Sensor at {}: closest beacon is at {}", Vec2<i64>, Vec2<i64>)?;
+        let distance = sensor.manhattan(beacon);
 
-        let (x, line) = find_coordinate(line, '=', Some(','));
-        let (y, _) = find_coordinate(line, '=', None);
-        let beacon = vec2!(i64::from_line(x), i64::from_line(y));
-        let distance = (sensor.x() - beacon.x()).abs() + (sensor.y() - beacon.y()).abs();
-
-        Self {
+        Ok(Self {
             position: sensor,
+            beacon,
             distance,
-        }
+        })
     }
-}
\ No newline at end of file
+}
@@ -1,33 +1,49 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, BTreeMap, HashSet};
-use util::{FromLine, FromLines, read, run};
+use std::cmp::Reverse;
+use std::collections::HashMap;
 
-mod util;
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
+use crate::util::parameters;
+use crate::util::search::bfs;
 
-const TIME_ALLOWED: u64 = 30;
+parameters! {
+    pub struct Parameters {
+        time_allowed: u64 = 30,
+    }
+}
+
+#[allow(unused)]
 const TIME_PER_ACTION: u64 = 1;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day16.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Input;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn part_1(input: &Input) -> u64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u64 {
+        input.part_2()
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn parameters(input: &mut Input) -> Option<&mut dyn parameters::Parameters> {
+        Some(&mut input.parameters)
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     system: PressureSystem,
+    parameters: Parameters,
 }
 
 impl Input {
     fn part_1(&self) -> u64 {
-        let search = self.system.search(Id(['A', 'A']), 30);
-        for item in search.released_pressures {
-            println!("{:?} - {}", item.id, item.released_pressure);
-        }
+        // Not solved yet : only the pressure released by opening a single valve is known.
+        let _search = self.system.search(Id(['A', 'A']), self.parameters.time_allowed);
         0
     }
 
@@ -36,13 +52,14 @@ impl Input {
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let system = PressureSystem::from_lines(lines);
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let system = PressureSystem::try_from_lines(lines)?;
 
-        Self {
-            system
-        }
+        Ok(Self {
+            system,
+            parameters: Parameters::default(),
+        })
     }
 }
 
@@ -56,74 +73,19 @@ struct PressureSystem {
 
 impl PressureSystem {
     fn search(&self, target: Id, step: u64) -> PressureSearch {
-        #[derive(Debug, Eq, PartialEq)]
-        struct Node(Id, u64);
-
-        // Reverse order. Smaller cost gets prioritised first.
-        impl Ord for Node {
-            fn cmp(&self, other: &Self) -> Ordering {
-                other.1.cmp(&self.1)
-            }
-        }
-
-        impl PartialOrd for Node {
-            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-                Some(self.cmp(other))
-            }
-        }
-
-        // All valves.
-        let positions: Vec<Id> = self.valves.keys().cloned().collect();
-
-        // Visited valves.
-        let mut visited: HashSet<Id> = HashSet::with_capacity(positions.len());
-
-        // Valves to visit.
-        let mut visit_queue: BinaryHeap<Node> = BinaryHeap::with_capacity(positions.len());
-        visit_queue.push(Node(target, 0));
-
-        // Distance from end to every other valves. Defaults to infinity.
-        // Distance to end is 0. If end doesn't exist in distances, nothing happens.
-        let mut distances: HashMap<Id, u64> = positions.iter().map(|it| (*it, u64::MAX)).collect();
-        distances.entry(target).and_modify(|distance| *distance = 0);
-
-        // Map a valve to the next valve to go to get closer to the end. Default to None.
-        let mut previous: HashMap<Id, Option<Id>> = positions.iter().map(|it| (*it, None)).collect();
-
-        // As long as there is valves to visit.
-        while let Some(Node(current, distance)) = visit_queue.pop() {
-            // For all neighbours of this position.
-            for neighbour in &self.valves.get(&current).expect("node should exist").tunnels {
-                // Mark position as visited, or skip if already visited.
-                if visited.insert(*neighbour) {
-                    // Distance from current to this neighbour is current distance plus one.
-                    let new_distance = distance + 1;
-
-                    // Visit this neighbour neighbour's next iteration.
-                    visit_queue.push(Node(*neighbour, new_distance));
-
-                    // Update known distance to this position, if smaller.
-                    let neighbour_distance = distances.get_mut(&neighbour).expect("distances should have all nodes");
-                    if new_distance < *neighbour_distance {
-                        let neighbour_previous = previous.get_mut(&neighbour).expect("previous should have all nodes");
-
-                        *neighbour_distance = new_distance;
-                        *neighbour_previous = Some(current);
-                    }
-                }
-            }
-        }
+        let search = bfs([target], |it| self.valves.get(it).expect("valve should exist").tunnels.iter().copied());
 
         // Compute released pressure for each valve
-        let mut pressures = Vec::with_capacity(positions.len());
-        for (id, distance) in distances.iter() {
+        let mut pressures = Vec::with_capacity(self.valves.len());
+        for (id, distance) in search.reached() {
             let valve = self.valves.get(id).expect("valve should exist");
             pressures.push(PressureSearchItem {
                 id: *id,
-                released_pressure: (step - distance - 2) * valve.flow_rate,
+                // Valves out of reach in time release nothing.
+                released_pressure: step.saturating_sub(distance as u64 + 2) * valve.flow_rate,
             })
         }
-        pressures.sort_by(|lhs, rhs| rhs.released_pressure.cmp(&lhs.released_pressure));
+        pressures.sort_by_key(|it| Reverse(it.released_pressure));
 
         PressureSearch {
             released_pressures: pressures
@@ -131,11 +93,13 @@ impl PressureSystem {
     }
 }
 
+#[allow(unused)]
 #[derive(Debug)]
 struct PressureSearch {
     released_pressures: Vec<PressureSearchItem>,
 }
 
+#[allow(unused)]
 #[derive(Debug)]
 struct PressureSearchItem {
     id: Id,
@@ -149,42 +113,43 @@ struct Valve {
     tunnels: Vec<Id>,
 }
 
-impl FromLine for Id {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for Id {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         let mut parts = line.chars();
-        Self([
-            parts.next().expect("id should have 2 characters"),
-            parts.next().expect("id should have 2 characters")
-        ])
+        match (parts.next(), parts.next()) {
+            (Some(first), Some(second)) => Ok(Self([first, second])),
+            _ => Err(ParseError::new(line, "id should have 2 characters"))
+        }
     }
 }
 
-impl FromLines for PressureSystem {
-    fn from_lines(lines: &[&str]) -> Self {
-        let valves = lines.iter().map(line_to!(Valve)).map(|it| (it.id, it)).collect();
+impl TryFromLines for PressureSystem {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let valves = lines.iter().map(try_line_to!(Valve)).map(|it| it.map(|it| (it.id, it))).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             valves
-        }
+        })
     }
 }
 
-impl FromLine for Valve {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for Valve {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         let mut parts = line.split(' ');
-        let id = parts.nth(1).expect("valve should have an id");
-        let id = Id::from_line(id);
+        let id = parts.nth(1).ok_or_else(|| ParseError::new(line, "valve should have an id"))?;
+        let id = Id::try_from_line(id)?;
 
-        let flow_rate = parts.nth(2).expect("valve should have a flow_rate");
-        let flow_rate = u64::from_line(&flow_rate[5..flow_rate.len() - 1]);
+        let flow_rate = parts.nth(2).ok_or_else(|| ParseError::new(line, "valve should have a flow rate"))?;
+        let flow_rate = flow_rate.get(5..flow_rate.len().saturating_sub(1)).ok_or_else(|| ParseError::new(flow_rate, "flow rate is not valid"))?;
+        let flow_rate = u64::try_from_line(flow_rate)?;
 
         let tunnels = parts.skip(4);
-        let tunnels = tunnels.map(line_to!(Id)).collect();
+        let tunnels = tunnels.map(try_line_to!(Id)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             id,
             flow_rate,
             tunnels,
-        }
+        })
     }
 }
\ No newline at end of file
@@ -1,28 +1,205 @@
-use util::{FromChar, FromLine, FromLines, read};
+use std::array;
+use std::collections::HashMap;
 
-mod util;
+use crate::util::{chars_to, ParseError, ParseResult, Solution, TryFromChar, TryFromLines};
 
-fn main() {
-    let input: Input = read("inputs/day17.txt");
-    println!("Part 1 : {}", input.part_1());
-    println!("Part 2 : {}", input.part_2());
+const PART_1_ROCKS: u64 = 2022;
+const PART_2_ROCKS: u64 = 1_000_000_000_000;
+
+// Chamber rows are bytes, one bit per column. The left wall is past the 7th bit.
+const CHAMBER_WIDTH: u32 = 7;
+const LEFT_COLUMN: u8 = 1 << (CHAMBER_WIDTH - 1);
+const RIGHT_COLUMN: u8 = 1;
+
+// Rocks are at most 4 rows high, so their rows are packed in a u32, one byte per row from
+// the lowest one. Rows are the same as chamber rows.
+const ROCK_HEIGHT: usize = 4;
+const LEFT_COLUMNS: u32 = u32::from_le_bytes([LEFT_COLUMN; ROCK_HEIGHT]);
+const RIGHT_COLUMNS: u32 = u32::from_le_bytes([RIGHT_COLUMN; ROCK_HEIGHT]);
+
+// Rows of each rock, from the bottom, already two units away from the left wall.
+const ROCKS: [u32; 5] = [
+    u32::from_le_bytes([0b0011110, 0, 0, 0]),
+    u32::from_le_bytes([0b0001000, 0b0011100, 0b0001000, 0]),
+    u32::from_le_bytes([0b0011100, 0b0000100, 0b0000100, 0]),
+    u32::from_le_bytes([0b0010000, 0b0010000, 0b0010000, 0b0010000]),
+    u32::from_le_bytes([0b0011000, 0b0011000, 0, 0]),
+];
+
+// Rocks appear this many rows above the highest rock.
+const SPAWN_HEIGHT: usize = 3;
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Input;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn part_1(input: &Input) -> u64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u64 {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {}
+pub struct Input {
+    jets: Vec<Jet>,
+}
 
 impl Input {
     fn part_1(&self) -> u64 {
-        0
+        Chamber::new(&self.jets).height_after(PART_1_ROCKS)
     }
 
     fn part_2(&self) -> u64 {
-        0
+        Chamber::new(&self.jets).height_after(PART_2_ROCKS)
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        todo!()
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Jet {
+    Left,
+    Right,
+}
+
+// Where the rocks fall. Rows go from the floor up, and only hold settled rocks.
+#[derive(Debug)]
+struct Chamber<'a> {
+    jets: &'a [Jet],
+    rows: Vec<u8>,
+    // Height of the highest rock in each column, from the left. Kept while rocks settle, so the
+    // tower is not scanned for each fingerprint.
+    tops: [usize; CHAMBER_WIDTH as usize],
+    jet: usize,
+    rock: usize,
+}
+
+// What the simulation depends on : which rock and jet come next, and the shape of the top
+// of the tower. Seeing it twice means the simulation repeats itself from there.
+#[derive(Debug, Eq, PartialEq, Hash)]
+struct Fingerprint {
+    rock: usize,
+    jet: usize,
+    skyline: [usize; CHAMBER_WIDTH as usize],
+}
+
+impl<'a> Chamber<'a> {
+    fn new(jets: &'a [Jet]) -> Self {
+        Self {
+            jets,
+            rows: Vec::new(),
+            tops: [0; CHAMBER_WIDTH as usize],
+            jet: 0,
+            rock: 0,
+        }
     }
-}
\ No newline at end of file
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    // Simulating a trillion rocks would take ages. Once a cycle is found, whole cycles are
+    // skipped, and the remaining rocks are simulated.
+    fn height_after(&mut self, rocks: u64) -> u64 {
+        let mut seen: HashMap<Fingerprint, (u64, usize)> = HashMap::new();
+        let mut skipped_height = 0;
+        let mut is_skipped = false;
+
+        let mut count = 0;
+        while count < rocks {
+            self.drop_rock();
+            count += 1;
+
+            if !is_skipped {
+                if let Some((previous_count, previous_height)) = seen.insert(self.fingerprint(), (count, self.height())) {
+                    let cycle_length = count - previous_count;
+                    let cycle_height = (self.height() - previous_height) as u64;
+
+                    let cycles = (rocks - count) / cycle_length;
+                    count += cycles * cycle_length;
+                    skipped_height = cycles * cycle_height;
+                    is_skipped = true;
+                }
+            }
+        }
+        self.height() as u64 + skipped_height
+    }
+
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.rock];
+        self.rock = (self.rock + 1) % ROCKS.len();
+
+        // Bottom of the rock.
+        let mut y = self.height() + SPAWN_HEIGHT;
+        loop {
+            // Pushed by a jet, unless something is in the way.
+            let jet = self.jets[self.jet];
+            self.jet = (self.jet + 1) % self.jets.len();
+
+            let pushed = match jet {
+                Jet::Left if rock & LEFT_COLUMNS == 0 => Some(rock << 1),
+                Jet::Right if rock & RIGHT_COLUMNS == 0 => Some(rock >> 1),
+                _ => None,
+            };
+            if let Some(pushed) = pushed.filter(|it| !self.collides(*it, y)) {
+                rock = pushed;
+            }
+
+            // Falls, unless something is below.
+            if y == 0 || self.collides(rock, y - 1) { break; }
+            y -= 1;
+        }
+
+        // Settle. Empty rows are above the rock.
+        for (i, row) in rock.to_le_bytes().into_iter().take_while(|it| *it != 0).enumerate() {
+            if y + i >= self.rows.len() { self.rows.push(0); }
+            self.rows[y + i] |= row;
+
+            for (column, top) in self.tops.iter_mut().enumerate() {
+                if row & (LEFT_COLUMN >> column) != 0 { *top = (*top).max(y + i + 1); }
+            }
+        }
+    }
+
+    fn collides(&self, rock: u32, y: usize) -> bool {
+        let rows: [u8; ROCK_HEIGHT] = array::from_fn(|i| self.rows.get(y + i).copied().unwrap_or(0));
+        u32::from_le_bytes(rows) & rock != 0
+    }
+
+    // Depth of the highest rock in each column, from the top of the tower.
+    fn fingerprint(&self) -> Fingerprint {
+        let skyline = self.tops.map(|it| self.height() - it);
+
+        Fingerprint {
+            rock: self.rock,
+            jet: self.jet,
+            skyline,
+        }
+    }
+}
+
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let line = lines.first().ok_or_else(|| ParseError::missing("input should have a jet pattern"))?;
+        let jets: Vec<Jet> = chars_to(line).collect::<ParseResult<_>>()?;
+        if jets.is_empty() { return Err(ParseError::new(line, "jet pattern should not be empty")); }
+
+        Ok(Self {
+            jets
+        })
+    }
+}
+
+impl TryFromChar for Jet {
+    fn try_from_char(char: char) -> ParseResult<Self> {
+        match char {
+            '<' => Ok(Self::Left),
+            '>' => Ok(Self::Right),
+            _ => Err(ParseError::for_char(char, "jet should be < or >")),
+        }
+    }
+}
@@ -1,28 +1,81 @@
-use util::{FromChar, FromLine, FromLines, read};
+use std::collections::HashSet;
 
-mod util;
+use crate::util::{BoundingBox, ParseResult, Solution, TryFromLine, TryFromLines, Vec3};
+use crate::util::search::bfs;
 
-fn main() {
-    let input: Input = read("inputs/day18.txt");
-    println!("Part 1 : {}", input.part_1());
-    println!("Part 2 : {}", input.part_2());
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {}
+pub struct Input {
+    droplet: Droplet,
+}
 
 impl Input {
-    fn part_1(&self) -> u64 {
-        0
+    fn part_1(&self) -> usize {
+        self.droplet.surface()
     }
 
-    fn part_2(&self) -> u64 {
-        0
+    fn part_2(&self) -> usize {
+        self.droplet.exterior_surface()
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        todo!()
+type Position = Vec3<i64>;
+
+#[derive(Debug)]
+struct Droplet {
+    cubes: HashSet<Position>,
+}
+
+impl Droplet {
+    // Faces which are not against an other cube.
+    fn surface(&self) -> usize {
+        self.cubes
+            .iter()
+            .flat_map(|it| it.neighbours_6())
+            .filter(|it| !self.cubes.contains(it))
+            .count()
     }
-}
\ No newline at end of file
+
+    // Faces which are reachable by the air outside. Air pockets inside the droplet don't count.
+    fn exterior_surface(&self) -> usize {
+        // A margin around the droplet, so the air goes all around it.
+        let Some(bounds) = BoundingBox::of(self.cubes.iter().copied()).map(|it| it.expand(1)) else { return 0; };
+
+        let air = bfs([bounds.min()], |it: &Position| {
+            it.neighbours_6().filter(|it| bounds.contains(*it) && !self.cubes.contains(it)).collect::<Vec<_>>()
+        });
+
+        self.cubes
+            .iter()
+            .flat_map(|it| it.neighbours_6())
+            .filter(|it| air.is_reached(it))
+            .count()
+    }
+}
+
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let cubes = lines.iter().map(try_line_to!(Position)).collect::<ParseResult<_>>()?;
+
+        Ok(Self {
+            droplet: Droplet {
+                cubes
+            }
+        })
+    }
+}
@@ -1,28 +1,196 @@
-use util::{FromChar, FromLine, FromLines, read};
+use std::thread;
 
-mod util;
+use crate::util::{ParseResult, Solution, TryFromLine, TryFromLines};
 
-fn main() {
-    let input: Input = read("inputs/day19.txt");
-    println!("Part 1 : {}", input.part_1());
-    println!("Part 2 : {}", input.part_2());
+const PART_1_TIME: u32 = 24;
+const PART_2_TIME: u32 = 32;
+const PART_2_BLUEPRINTS: usize = 3;
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Input;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn part_1(input: &Input) -> u32 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u32 {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {}
+pub struct Input {
+    blueprints: Vec<Blueprint>,
+}
 
 impl Input {
-    fn part_1(&self) -> u64 {
-        0
+    fn part_1(&self) -> u32 {
+        self.blueprints
+            .iter()
+            .zip(max_geodes(&self.blueprints, PART_1_TIME))
+            .map(|(blueprint, geodes)| blueprint.id * geodes)
+            .sum()
+    }
+
+    fn part_2(&self) -> u32 {
+        let blueprints = &self.blueprints[..self.blueprints.len().min(PART_2_BLUEPRINTS)];
+        max_geodes(blueprints, PART_2_TIME).into_iter().product()
+    }
+}
+
+// Blueprints are independent, so they are searched in parallel, like day 15.
+fn max_geodes(blueprints: &[Blueprint], time: u32) -> Vec<u32> {
+    let thread_count = thread::available_parallelism().map(|it| it.get()).unwrap_or(1);
+
+    let mut geodes = vec![0; blueprints.len()];
+    thread::scope(|s| {
+        // Each thread takes every nth blueprint. Search times vary a lot between blueprints.
+        let handles: Vec<_> = (0..thread_count.min(blueprints.len()))
+            .map(|i| {
+                s.spawn(move || {
+                    (i..blueprints.len())
+                        .step_by(thread_count)
+                        .map(|it| (it, blueprints[it].max_geodes(time)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            for (index, value) in handle.join().expect("blueprint search should not panic") {
+                geodes[index] = value;
+            }
+        }
+    });
+    geodes
+}
+
+// Resources, and robots, are indexed in this order.
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+type Resources = [u32; 4];
+
+#[derive(Debug)]
+struct Blueprint {
+    id: u32,
+    // Cost of each robot.
+    costs: [Resources; 4],
+}
+
+#[derive(Debug, Copy, Clone)]
+struct State {
+    time_left: u32,
+    robots: Resources,
+    resources: Resources,
+}
+
+impl Blueprint {
+    fn max_geodes(&self, time: u32) -> u32 {
+        // A robot can be built each minute. Collecting more of a resource than can be spent in
+        // a minute is useless. Geodes are never spent.
+        let mut max_robots = [0; 4];
+        for cost in &self.costs {
+            for resource in [ORE, CLAY, OBSIDIAN] {
+                max_robots[resource] = max_robots[resource].max(cost[resource]);
+            }
+        }
+        max_robots[GEODE] = u32::MAX;
+
+        let start = State {
+            time_left: time,
+            robots: [1, 0, 0, 0],
+            resources: [0; 4],
+        };
+
+        let mut best = 0;
+        self.search(start, &max_robots, &mut best);
+        best
+    }
+
+    // Depth first, on the next robot to build. Waiting without building is only useful at the
+    // end, so it is accounted for by what the current robots will collect.
+    fn search(&self, state: State, max_robots: &Resources, best: &mut u32) {
+        let geodes = state.resources[GEODE] + state.robots[GEODE] * state.time_left;
+        *best = (*best).max(geodes);
+
+        // Optimistic : a geode robot is built every minute from now on.
+        let bound = geodes + state.time_left * state.time_left.saturating_sub(1) / 2;
+        if bound <= *best { return; }
+
+        // Geode robots first, since they give good bounds sooner.
+        for robot in [GEODE, OBSIDIAN, CLAY, ORE] {
+            if state.robots[robot] >= max_robots[robot] { continue; }
+
+            if let Some(next) = self.build(&state, robot) {
+                self.search(next, max_robots, best);
+            }
+        }
     }
 
-    fn part_2(&self) -> u64 {
-        0
+    // Wait for the resources, then build the robot. None if it can't be built in time.
+    fn build(&self, state: &State, robot: usize) -> Option<State> {
+        let cost = &self.costs[robot];
+
+        let mut wait = 0;
+        for resource in [ORE, CLAY, OBSIDIAN] {
+            if cost[resource] <= state.resources[resource] { continue; }
+            if state.robots[resource] == 0 { return None; }
+
+            let missing = cost[resource] - state.resources[resource];
+            wait = wait.max(missing.div_ceil(state.robots[resource]));
+        }
+
+        // A robot built in the last minute would not collect anything.
+        let elapsed = wait + 1;
+        if elapsed >= state.time_left { return None; }
+
+        let mut next = *state;
+        next.time_left -= elapsed;
+        for resource in [ORE, CLAY, OBSIDIAN, GEODE] {
+            next.resources[resource] = next.resources[resource] + state.robots[resource] * elapsed - cost[resource];
+        }
+        next.robots[robot] += 1;
+        Some(next)
+    }
+}
+
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let blueprints = lines
+            .iter()
+            .filter(|it| !it.trim().is_empty())
+            .map(try_line_to!(Blueprint))
+            .collect::<ParseResult<_>>()?;
+
+        Ok(Self {
+            blueprints
+        })
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        todo!()
+impl TryFromLine for Blueprint {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let (id, ore_robot, clay_robot, obsidian_robot_ore, obsidian_robot_clay, geode_robot_ore, geode_robot_obsidian) = parse!(
+            line,
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+            u32, u32, u32, u32, u32, u32, u32
+        )?;
+
+        Ok(Self {
+            id,
+            costs: [
+                [ore_robot, 0, 0, 0],
+                [clay_robot, 0, 0, 0],
+                [obsidian_robot_ore, obsidian_robot_clay, 0, 0],
+                [geode_robot_ore, 0, geode_robot_obsidian, 0],
+            ],
+        })
     }
-}
\ No newline at end of file
+}
@@ -1,19 +1,36 @@
-use util::{FromLine, FromLines, read, run};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
+use crate::util::stream::{StreamSolution, TryFromLineStream};
 
-mod util;
+pub struct Day2;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day2.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+impl Solution for Day2 {
+    type Input = Input;
+    type Part1 = u64;
+    type Part2 = u64;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_1(input: &Input) -> u64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u64 {
+        input.part_2()
+    }
+}
+
+impl StreamSolution for Day2 {
+    type Stream = Scores;
+
+    fn stream_part_1(stream: &Scores) -> u64 {
+        stream.part_1
+    }
+
+    fn stream_part_2(stream: &Scores) -> u64 {
+        stream.part_2
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     rounds: Vec<Round>,
 }
 
@@ -21,24 +38,14 @@ impl Input {
     fn part_1(&self) -> u64 {
         self.rounds
             .iter()
-            .map(|it| {
-                let player = it.player;
-                let opponent = it.opponent;
-                let outcome = player.outcome_for(opponent);
-                outcome.score() + player.score()
-            })
+            .map(|it| it.part_1_score())
             .sum()
     }
 
     fn part_2(&self) -> u64 {
         self.rounds
             .iter()
-            .map(|it| {
-                let outcome = it.outcome;
-                let opponent = it.opponent;
-                let player = opponent.for_outcome(outcome);
-                outcome.score() + player.score()
-            })
+            .map(|it| it.part_2_score())
             .sum()
     }
 }
@@ -50,6 +57,27 @@ struct Round {
     outcome: Outcome,
 }
 
+impl Round {
+    // The right column is the choice of the player.
+    fn part_1_score(&self) -> u64 {
+        let outcome = self.player.outcome_for(self.opponent);
+        outcome.score() + self.player.score()
+    }
+
+    // The right column is the outcome of the round.
+    fn part_2_score(&self) -> u64 {
+        let player = self.opponent.for_outcome(self.outcome);
+        self.outcome.score() + player.score()
+    }
+}
+
+// Scores of both parts, summed as the rounds come. Rounds are not kept.
+#[derive(Debug, Default)]
+pub struct Scores {
+    part_1: u64,
+    part_2: u64,
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Choice {
     Rock = 1,
@@ -110,46 +138,61 @@ impl Outcome {
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let rounds = lines.iter().map(line_to!(Round)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let rounds = lines.iter().map(try_line_to!(Round)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             rounds
-        }
+        })
     }
 }
 
-impl FromLine for Round {
-    fn from_line(line: &str) -> Self {
-        let (lhs, rhs) = line.split_once(' ').expect("round should have a left and a right part");
+impl TryFromLineStream for Scores {
+    type State = Self;
 
-        Self {
-            player: Choice::from_line(rhs),
-            opponent: Choice::from_line(lhs),
-            outcome: Outcome::from_line(rhs),
-        }
+    fn try_push_line(scores: &mut Self, line: &str) -> ParseResult<()> {
+        let round = Round::try_from_line(line)?;
+        scores.part_1 += round.part_1_score();
+        scores.part_2 += round.part_2_score();
+        Ok(())
+    }
+
+    fn try_finish(scores: Self) -> ParseResult<Self> {
+        Ok(scores)
+    }
+}
+
+impl TryFromLine for Round {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let (lhs, rhs) = line.split_once(' ').ok_or_else(|| ParseError::new(line, "round should have a left and a right part"))?;
+
+        Ok(Self {
+            player: Choice::try_from_line(rhs)?,
+            opponent: Choice::try_from_line(lhs)?,
+            outcome: Outcome::try_from_line(rhs)?,
+        })
     }
 }
 
-impl FromLine for Choice {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for Choice {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         match line {
-            "A" | "X" => Self::Rock,
-            "B" | "Y" => Self::Paper,
-            "C" | "Z" => Self::Scissors,
-            _ => panic!("\"{line}\" is not va valid choice")
+            "A" | "X" => Ok(Self::Rock),
+            "B" | "Y" => Ok(Self::Paper),
+            "C" | "Z" => Ok(Self::Scissors),
+            _ => Err(ParseError::new(line, "choice is not valid"))
         }
     }
 }
 
-impl FromLine for Outcome {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for Outcome {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         match line {
-            "X" => Self::Loss,
-            "Y" => Self::Draw,
-            "Z" => Self::Win,
-            _ => panic!("\"{line}\" is not a valid outcome")
+            "X" => Ok(Self::Loss),
+            "Y" => Ok(Self::Draw),
+            "Z" => Ok(Self::Win),
+            _ => Err(ParseError::new(line, "outcome is not valid"))
         }
     }
-}
\ No newline at end of file
+}
@@ -1,28 +1,144 @@
-use util::{FromChar, FromLine, FromLines, read};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
 
-mod util;
+const DECRYPTION_KEY: i64 = 811_589_153;
+const PART_2_ROUNDS: usize = 10;
 
-fn main() {
-    let input: Input = read("inputs/day20.txt");
-    println!("Part 1 : {}", input.part_1());
-    println!("Part 2 : {}", input.part_2());
+// Offsets of the grove coordinates, from the zero.
+const COORDINATE_OFFSETS: [usize; 3] = [1000, 2000, 3000];
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Input;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn part_1(input: &Input) -> i64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> i64 {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {}
+pub struct Input {
+    numbers: Vec<i64>,
+}
 
 impl Input {
-    fn part_1(&self) -> u64 {
-        0
+    fn part_1(&self) -> i64 {
+        grove_coordinates(&self.numbers, 1)
+    }
+
+    fn part_2(&self) -> i64 {
+        let numbers: Vec<i64> = self.numbers.iter().map(|it| it * DECRYPTION_KEY).collect();
+        grove_coordinates(&numbers, PART_2_ROUNDS)
+    }
+}
+
+fn grove_coordinates(numbers: &[i64], rounds: usize) -> i64 {
+    let Some(zero) = numbers.iter().position(|it| *it == 0) else { return 0; };
+
+    // Numbers can repeat, so they are moved by their original index.
+    let mut sequence = Sequence::new(numbers.len());
+    for _ in 0..rounds {
+        for (index, number) in numbers.iter().enumerate() {
+            sequence.shift(index, *number);
+        }
+    }
+
+    let mixed: Vec<usize> = sequence.iter().collect();
+    let start = mixed.iter().position(|it| *it == zero).expect("zero should be in the sequence");
+    COORDINATE_OFFSETS
+        .iter()
+        .map(|it| numbers[mixed[(start + it) % mixed.len()]])
+        .sum()
+}
+
+// Circular sequence of the original indices. Moving an element inside a Vec shifts
+// everything after it, so the sequence is split in blocks of about √n elements. Finding an
+// element, removing it and inserting it only touches the block counts and a single block.
+#[derive(Debug)]
+struct Sequence {
+    blocks: Vec<Vec<usize>>,
+    // Block holding each element.
+    block_of: Vec<usize>,
+    block_size: usize,
+    len: usize,
+}
+
+impl Sequence {
+    fn new(len: usize) -> Self {
+        let block_size = (len as f64).sqrt().ceil().max(1.0) as usize;
+        let mut sequence = Self {
+            blocks: Vec::new(),
+            block_of: vec![0; len],
+            block_size,
+            len,
+        };
+        sequence.rebuild((0..len).collect());
+        sequence
+    }
+
+    // Split in blocks of the same size again, once some have grown too much.
+    fn rebuild(&mut self, elements: Vec<usize>) {
+        self.blocks = elements.chunks(self.block_size).map(|it| it.to_vec()).collect();
+        for (block, elements) in self.blocks.iter().enumerate() {
+            for element in elements {
+                self.block_of[*element] = block;
+            }
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item=usize> + '_ {
+        self.blocks.iter().flatten().copied()
     }
 
-    fn part_2(&self) -> u64 {
-        0
+    // Moves an element forward (or backward), wrapping around. The element is not part of
+    // the sequence while it moves, so a full turn is one less than the length.
+    fn shift(&mut self, element: usize, offset: i64) {
+        if self.len < 2 { return; }
+
+        let position = self.remove(element);
+        let position = (position as i64 + offset).rem_euclid(self.len as i64 - 1) as usize;
+        self.insert(position, element);
+    }
+
+    fn remove(&mut self, element: usize) -> usize {
+        let block = self.block_of[element];
+        let offset = self.blocks[..block].iter().map(|it| it.len()).sum::<usize>();
+        let index = self.blocks[block].iter().position(|it| *it == element).expect("element should be in its block");
+        self.blocks[block].remove(index);
+        offset + index
+    }
+
+    fn insert(&mut self, mut position: usize, element: usize) {
+        // At the end of a block, rather than at the start of the next one, so the last block
+        // takes elements inserted at the very end.
+        let mut block = 0;
+        while position > self.blocks[block].len() {
+            position -= self.blocks[block].len();
+            block += 1;
+        }
+        self.blocks[block].insert(position, element);
+        self.block_of[element] = block;
+
+        if self.blocks[block].len() > self.block_size * 2 {
+            let elements = self.iter().collect();
+            self.rebuild(elements);
+        }
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        todo!()
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let numbers: Vec<i64> = lines.iter().map(try_line_to!(i64)).collect::<ParseResult<_>>()?;
+        if numbers.iter().filter(|it| **it == 0).count() != 1 { return Err(ParseError::missing("input should have a single zero")); }
+
+        Ok(Self {
+            numbers
+        })
     }
-}
\ No newline at end of file
+}
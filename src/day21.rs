@@ -1,28 +1,234 @@
-use util::{FromChar, FromLine, FromLines, read};
+use std::collections::HashMap;
 
-mod util;
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
 
-fn main() {
-    let input: Input = read("inputs/day21.txt");
-    println!("Part 1 : {}", input.part_1());
-    println!("Part 2 : {}", input.part_2());
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Input;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn part_1(input: &Input) -> i64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> i64 {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {}
+pub struct Input {
+    monkeys: Monkeys,
+}
 
 impl Input {
-    fn part_1(&self) -> u64 {
-        0
+    fn part_1(&self) -> i64 {
+        self.monkeys.values[self.monkeys.root]
     }
 
-    fn part_2(&self) -> u64 {
-        0
+    fn part_2(&self) -> i64 {
+        self.monkeys.solve_human()
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        todo!()
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    fn apply(&self, left: i64, right: i64) -> i64 {
+        match self {
+            Operation::Add => left + right,
+            Operation::Subtract => left - right,
+            Operation::Multiply => left * right,
+            Operation::Divide => left / right,
+        }
+    }
+
+    // Value of the left operand, so that the operation gives the result.
+    fn solve_left(&self, result: i64, right: i64) -> i64 {
+        match self {
+            Operation::Add => result - right,
+            Operation::Subtract => result + right,
+            Operation::Multiply => exact_division(result, right),
+            Operation::Divide => result * right,
+        }
+    }
+
+    // Same, for the right operand.
+    fn solve_right(&self, result: i64, left: i64) -> i64 {
+        match self {
+            Operation::Add => result - left,
+            Operation::Subtract => left - result,
+            Operation::Multiply => exact_division(result, left),
+            Operation::Divide => exact_division(left, result),
+        }
     }
-}
\ No newline at end of file
+}
+
+// The puzzle has a single integer solution, so the divisions going down to the human are exact
+// (and not by zero). Otherwise, there is no answer to give, which is a bug of the input.
+fn exact_division(dividend: i64, divisor: i64) -> i64 {
+    if divisor == 0 || dividend % divisor != 0 { panic!("{HUMAN} should have an integer solution ({dividend} / {divisor})"); }
+    dividend / divisor
+}
+
+// Jobs refer to other monkeys by index.
+#[derive(Debug, Copy, Clone)]
+enum Job {
+    Number(i64),
+    Operation(usize, Operation, usize),
+}
+
+// Expressions form a DAG. Monkeys are sorted so that a monkey comes after the ones it waits
+// for, so each value is computed once, without recursion.
+#[derive(Debug)]
+struct Monkeys {
+    jobs: Vec<Job>,
+    order: Vec<usize>,
+    // Values yelled by each monkey. Computed once, since both parts need them, and a division by
+    // zero is an error of the input.
+    values: Vec<i64>,
+    root: usize,
+    human: usize,
+}
+
+impl Monkeys {
+    // Root compares both sides. Going down from the root, the side depending on the human must
+    // give the value which makes the operation right. The other side is known.
+    fn solve_human(&self) -> i64 {
+        let values = &self.values;
+
+        let mut depends_on_human = vec![false; self.jobs.len()];
+        depends_on_human[self.human] = true;
+        for &monkey in &self.order {
+            if let Job::Operation(left, _, right) = self.jobs[monkey] {
+                depends_on_human[monkey] = depends_on_human[left] || depends_on_human[right];
+            }
+        }
+
+        let Job::Operation(left, _, right) = self.jobs[self.root] else { panic!("{ROOT} should have an operation"); };
+        let (mut monkey, mut target) = match (depends_on_human[left], depends_on_human[right]) {
+            (true, false) => (left, values[right]),
+            (false, true) => (right, values[left]),
+            _ => panic!("{HUMAN} should be on a single side of {ROOT}"),
+        };
+
+        while monkey != self.human {
+            let Job::Operation(left, operation, right) = self.jobs[monkey] else { panic!("monkey depending on {HUMAN} should have an operation"); };
+            (monkey, target) = match (depends_on_human[left], depends_on_human[right]) {
+                (true, false) => (left, operation.solve_left(target, values[right])),
+                (false, true) => (right, operation.solve_right(target, values[left])),
+                _ => panic!("{HUMAN} should be on a single side of each operation"),
+            };
+        }
+        target
+    }
+}
+
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let monkeys = Monkeys::try_from_lines(lines)?;
+
+        Ok(Self {
+            monkeys
+        })
+    }
+}
+
+impl TryFromLines for Monkeys {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        // Names first, since jobs can refer to monkeys declared later.
+        let mut indices = HashMap::new();
+        let mut declarations = Vec::new();
+        for line in lines.iter().filter(|it| !it.trim().is_empty()) {
+            let (name, job) = line.split_once(':').ok_or_else(|| ParseError::new(line, "monkey should be a name and a job"))?;
+            let name = name.trim();
+            if indices.insert(name, declarations.len()).is_some() { return Err(ParseError::new(name, "monkey should be declared once")); }
+            declarations.push(job.trim());
+        }
+
+        let index = |name: &str| indices.get(name).copied().ok_or_else(|| ParseError::new(name, "monkey does not exist"));
+        let jobs = declarations
+            .iter()
+            .map(|job| {
+                let parts: Vec<&str> = job.split_whitespace().collect();
+                match parts[..] {
+                    [number] => Ok(Job::Number(i64::try_from_line(number)?)),
+                    [left, operation, right] => Ok(Job::Operation(index(left)?, Operation::try_from_line(operation)?, index(right)?)),
+                    _ => Err(ParseError::new(job, "job should be a number or an operation")),
+                }
+            })
+            .collect::<ParseResult<Vec<_>>>()?;
+
+        let root = indices.get(ROOT).copied().ok_or_else(|| ParseError::missing(format!("input should have a \"{ROOT}\" monkey")))?;
+        let human = indices.get(HUMAN).copied().ok_or_else(|| ParseError::missing(format!("input should have a \"{HUMAN}\" monkey")))?;
+        let order = sort(&jobs).ok_or_else(|| ParseError::missing("monkeys should not wait for themselves"))?;
+
+        let mut values = vec![0; jobs.len()];
+        for &monkey in &order {
+            values[monkey] = match jobs[monkey] {
+                Job::Number(value) => value,
+                Job::Operation(_, Operation::Divide, right) if values[right] == 0 => {
+                    return Err(ParseError::new(declarations[monkey], "monkey should not divide by zero"));
+                }
+                Job::Operation(left, operation, right) => operation.apply(values[left], values[right]),
+            };
+        }
+
+        Ok(Self {
+            jobs,
+            order,
+            values,
+            root,
+            human,
+        })
+    }
+}
+
+// Topological sort (Kahn). None if some monkeys wait for each other in a loop.
+fn sort(jobs: &[Job]) -> Option<Vec<usize>> {
+    let mut waiting = vec![0; jobs.len()];
+    let mut waited_by = vec![Vec::new(); jobs.len()];
+    for (monkey, job) in jobs.iter().enumerate() {
+        if let Job::Operation(left, _, right) = job {
+            for dependency in [*left, *right] {
+                waiting[monkey] += 1;
+                waited_by[dependency].push(monkey);
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..jobs.len()).filter(|it| waiting[*it] == 0).collect();
+    let mut next = 0;
+    while let Some(&monkey) = order.get(next) {
+        next += 1;
+        for &other in &waited_by[monkey] {
+            waiting[other] -= 1;
+            if waiting[other] == 0 { order.push(other); }
+        }
+    }
+
+    if order.len() == jobs.len() { Some(order) } else { None }
+}
+
+impl TryFromLine for Operation {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        match line {
+            "+" => Ok(Self::Add),
+            "-" => Ok(Self::Subtract),
+            "*" => Ok(Self::Multiply),
+            "/" => Ok(Self::Divide),
+            _ => Err(ParseError::new(line, "operation should be +, -, * or /")),
+        }
+    }
+}
@@ -1,15 +1,23 @@
-use util::{FromChar, FromLine, FromLines, read};
+use crate::util::{ParseResult, Solution, TryFromLines};
 
-mod util;
+pub struct Day23;
 
-fn main() {
-    let input: Input = read("inputs/day23.txt");
-    println!("Part 1 : {}", input.part_1());
-    println!("Part 2 : {}", input.part_2());
+impl Solution for Day23 {
+    type Input = Input;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn part_1(input: &Input) -> u64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u64 {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {}
+pub struct Input {}
 
 impl Input {
     fn part_1(&self) -> u64 {
@@ -21,8 +29,8 @@ impl Input {
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
+impl TryFromLines for Input {
+    fn try_from_lines(_lines: &[&str]) -> ParseResult<Self> {
         todo!()
     }
 }
\ No newline at end of file
@@ -1,19 +1,23 @@
-use util::{FromChar, FromLine, FromLines, read, run};
+use crate::util::{chars_to, ParseError, ParseResult, Solution, TryFromChar, TryFromLine, TryFromLines};
 
-mod util;
+pub struct Day3;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day3.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+impl Solution for Day3 {
+    type Input = Input;
+    type Part1 = u64;
+    type Part2 = u64;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_1(input: &Input) -> u64 {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> u64 {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     rucksacks: Vec<Rucksack>,
 }
 
@@ -46,8 +50,7 @@ impl Rucksack {
         let (lhs, rhs) = self.items.split_at(self.items.len() / 2);
 
         lhs.iter()
-            .find(|it| rhs.contains(*it))
-            .map(|it| *it)
+            .find(|it| rhs.contains(*it)).copied()
     }
 }
 
@@ -80,33 +83,33 @@ impl<'a> Group<'a> {
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let rucksacks = lines.iter().map(line_to!(Rucksack)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let rucksacks = lines.iter().map(try_line_to!(Rucksack)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             rucksacks
-        }
+        })
     }
 }
 
-impl FromLine for Rucksack {
-    fn from_line(line: &str) -> Self {
-        let items: Vec<Item> = line.chars().map(char_to!(Item)).collect();
+impl TryFromLine for Rucksack {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let items: Vec<Item> = chars_to(line).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             items
-        }
+        })
     }
 }
 
-impl FromChar for Item {
-    fn from_char(char: char) -> Self {
+impl TryFromChar for Item {
+    fn try_from_char(char: char) -> ParseResult<Self> {
         let code = char as u8;
         match code {
-            b'a'..=b'z' => Self(code - b'a' + 1),
-            b'A'..=b'Z' => Self(code - b'A' + 1 + 26),
-            _ => panic!("\"{char}\" is not a valid item")
+            b'a'..=b'z' => Ok(Self(code - b'a' + 1)),
+            b'A'..=b'Z' => Ok(Self(code - b'A' + 1 + 26)),
+            _ => Err(ParseError::for_char(char, "item is not valid"))
         }
     }
 }
\ No newline at end of file
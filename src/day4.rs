@@ -1,21 +1,37 @@
-use std::ops::RangeInclusive;
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
+use crate::util::interval::Interval;
+use crate::util::stream::{StreamSolution, TryFromLineStream};
 
-use util::{FromLine, FromLines, read, run};
+pub struct Day4;
 
-mod util;
+impl Solution for Day4 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day4.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
+}
+
+impl StreamSolution for Day4 {
+    type Stream = Overlaps;
+
+    fn stream_part_1(stream: &Overlaps) -> usize {
+        stream.full_overlaps
+    }
+
+    fn stream_part_2(stream: &Overlaps) -> usize {
+        stream.overlaps
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     pairs: Vec<Pair>,
 }
 
@@ -43,70 +59,56 @@ struct Pair {
 
 impl Pair {
     fn has_full_overlap(&self) -> bool {
-        let lhs_start = self.lhs.start();
-        let lhs_end = self.lhs.end();
-        let rhs_start = self.rhs.start();
-        let rhs_end = self.rhs.end();
-
         // Left contains right or right contains left.
-        (rhs_start <= lhs_start && rhs_end >= lhs_end) || (lhs_start <= rhs_start && lhs_end >= rhs_end)
+        self.lhs.contains_interval(&self.rhs) || self.rhs.contains_interval(&self.lhs)
     }
 
     fn has_overlap(&self) -> bool {
-        let lhs_start = self.lhs.start();
-        let lhs_end = self.lhs.end();
-        let rhs_start = self.rhs.start();
-        let rhs_end = self.rhs.end();
-
-        // Left overlap with right for at least one.
-        lhs_start <= rhs_end && lhs_end >= rhs_start
+        self.lhs.overlaps(&self.rhs)
     }
 }
 
-#[derive(Debug)]
-struct Assignment {
-    range: RangeInclusive<u64>,
-}
-
-impl Assignment {
-    fn start(&self) -> u64 {
-        *self.range.start()
-    }
+type Assignment = Interval<u64>;
 
-    fn end(&self) -> u64 {
-        *self.range.end()
-    }
+// Overlaps of both parts, counted as the pairs come. Pairs are not kept.
+#[derive(Debug, Default)]
+pub struct Overlaps {
+    full_overlaps: usize,
+    overlaps: usize,
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let pairs = lines.iter().map(line_to!(Pair)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let pairs = lines.iter().map(try_line_to!(Pair)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             pairs
-        }
+        })
     }
 }
 
-impl FromLine for Pair {
-    fn from_line(line: &str) -> Self {
-        let (lhs, rhs) = line.split_once(',').expect("pair should have a left and a right assignment");
+impl TryFromLineStream for Overlaps {
+    type State = Self;
+
+    fn try_push_line(overlaps: &mut Self, line: &str) -> ParseResult<()> {
+        let pair = Pair::try_from_line(line)?;
+        overlaps.full_overlaps += usize::from(pair.has_full_overlap());
+        overlaps.overlaps += usize::from(pair.has_overlap());
+        Ok(())
+    }
 
-        Self {
-            lhs: Assignment::from_line(lhs),
-            rhs: Assignment::from_line(rhs),
-        }
+    fn try_finish(overlaps: Self) -> ParseResult<Self> {
+        Ok(overlaps)
     }
 }
 
-impl FromLine for Assignment {
-    fn from_line(line: &str) -> Self {
-        let (start, end) = line.split_once('-').expect("assignment should have a start and a end value");
-        let start = u64::from_line(start);
-        let end = u64::from_line(end);
+impl TryFromLine for Pair {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let (lhs, rhs) = line.split_once(',').ok_or_else(|| ParseError::new(line, "pair should have a left and a right assignment"))?;
 
-        Self {
-            range: start..=end
-        }
+        Ok(Self {
+            lhs: Assignment::try_from_line(lhs)?,
+            rhs: Assignment::try_from_line(rhs)?,
+        })
     }
-}
\ No newline at end of file
+}
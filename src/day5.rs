@@ -1,19 +1,23 @@
-use util::{FromLine, FromLines, read, run};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
 
-mod util;
+pub struct Day5;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day5.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+impl Solution for Day5 {
+    type Input = Input;
+    type Part1 = String;
+    type Part2 = String;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_1(input: &Input) -> String {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> String {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     supplies: Supplies,
     commands: Vec<Command>,
 }
@@ -68,7 +72,7 @@ impl Stack {
     }
 
     fn peek(&self) -> Option<char> {
-        self.0.last().map(|it| *it)
+        self.0.last().copied()
     }
 
     fn pop(&mut self) -> char {
@@ -95,22 +99,23 @@ struct Command {
     destination: usize,
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
         let mut parts = lines.split(is_empty!());
-        let supplies = Supplies::from_lines(parts.next().expect("input should have supplies"));
-        let commands = parts.next().expect("input should have commands").iter().map(line_to!(Command)).collect();
+        let supplies = Supplies::try_from_lines(parts.next().ok_or_else(|| ParseError::missing("input should have supplies"))?)?;
+        let commands = parts.next().ok_or_else(|| ParseError::missing("input should have commands"))?;
+        let commands = commands.iter().map(try_line_to!(Command)).collect::<ParseResult<_>>()?;
 
-        Self {
+        Ok(Self {
             supplies,
             commands,
-        }
+        })
     }
 }
 
-impl FromLines for Supplies {
-    fn from_lines(lines: &[&str]) -> Self {
-        if lines.len() < 2 { panic!("supplies data should have at least one row (excluding the footer)"); }
+impl TryFromLines for Supplies {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        if lines.len() < 2 { return Err(ParseError::missing("supplies data should have at least one row (excluding the footer)")); }
 
         let lines = &lines[..lines.len() - 1]; // Last line (stack indexes) is not used.
         let nb_cols = (lines[0].len() + 1) / 4; // Each column takes 4 chars.
@@ -118,33 +123,26 @@ impl FromLines for Supplies {
         let mut stacks = vec![Stack::new(); nb_cols];
         for line in lines.iter().rev() {
             for (i, chunk) in line.chars().collect::<Vec<char>>().chunks(4).enumerate() {
-                let item = chunk.iter().nth(1).unwrap_or(&' ');
+                let item = chunk.get(1).unwrap_or(&' ');
                 if !item.is_whitespace() {
-                    stacks[i].push(*item)
+                    let stack = stacks.get_mut(i).ok_or_else(|| ParseError::new(line, "supplies row should not be wider than the first row"))?;
+                    stack.push(*item)
                 }
             }
         }
 
-        Self(stacks)
+        Ok(Self(stacks))
     }
 }
 
-impl FromLine for Command {
-    fn from_line(line: &str) -> Self {
-        let parts: Vec<&str> = line.splitn(6, ' ').collect();
-        match parts[..] {
-            ["move", count, "from", source, "to", destination] => {
-                let count = usize::from_line(count);
-                let source = usize::from_line(source);
-                let destination = usize::from_line(destination);
-
-                Self {
-                    count,
-                    source,
-                    destination,
-                }
-            }
-            _ => panic!("{line} is not a valid command")
-        }
+impl TryFromLine for Command {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let (count, source, destination) = parse!(line, "move {} from {} to {}", usize, usize, usize)?;
+
+        Ok(Self {
+            count,
+            source,
+            destination,
+        })
     }
 }
\ No newline at end of file
@@ -1,19 +1,23 @@
-use util::{FromLine, FromLines, read, run};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
 
-mod util;
+pub struct Day6;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day6.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+impl Solution for Day6 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     buffer: Buffer,
 }
 
@@ -34,7 +38,7 @@ impl Buffer {
     fn find_marker(&self, len: usize) -> Option<usize> {
         if self.0.len() < len { panic!("marker length should be smaller or equal to buffer length"); }
 
-        (len..self.0.len()).filter(move |it| {
+        (len..self.0.len()).find(move |it| {
             let slice = &self.0[*it - len..*it];
             for i in 0..len - 1 {
                 for j in i + 1..len {
@@ -42,24 +46,24 @@ impl Buffer {
                 }
             }
             true
-        }).next()
+        })
     }
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let buffer = Buffer::from_line(lines.get(0).expect("input should have a buffer"));
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let buffer = Buffer::try_from_line(lines.first().ok_or_else(|| ParseError::missing("input should have a buffer"))?)?;
 
-        Self {
+        Ok(Self {
             buffer
-        }
+        })
     }
 }
 
-impl FromLine for Buffer {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for Buffer {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         let buffer = line.chars().collect();
 
-        Self(buffer)
+        Ok(Self(buffer))
     }
 }
\ No newline at end of file
@@ -1,20 +1,37 @@
-use util::{FromLine, FromLines, read, run};
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines};
+use crate::util::parameters;
 
-mod util;
+parameters! {
+    pub struct Parameters {
+        disk_size: usize = 70_000_000,
+        update_size: usize = 30_000_000,
+    }
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day7.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn parameters(input: &mut Input) -> Option<&mut dyn parameters::Parameters> {
+        Some(&mut input.parameters)
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     file_system: FileSystem,
+    parameters: Parameters,
 }
 
 impl Input {
@@ -29,12 +46,13 @@ impl Input {
 
     fn part_2(&self) -> usize {
         let used_space = self.file_system.size();
-        let remaining_space = 70000000 - used_space;
+        // A disk smaller than the files is full.
+        let remaining_space = self.parameters.disk_size.saturating_sub(used_space);
 
         self.file_system
             .files
             .iter()
-            .filter(|it| it.is_directory && remaining_space + it.size > 30000000)
+            .filter(|it| it.is_directory && remaining_space + it.size > self.parameters.update_size)
             .map(|it| it.size)
             .min()
             .unwrap_or(0)
@@ -128,12 +146,12 @@ enum HistoryLine {
     File(String, usize),
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
         let mut file_system = FileSystem::new();
 
-        for line in lines.iter().map(line_to!(HistoryLine)) {
-            match line {
+        for line in lines.iter().map(try_line_to!(HistoryLine)) {
+            match line? {
                 HistoryLine::Cd(name) => file_system.navigate(&name),
                 HistoryLine::File(name, size) => file_system.add_file(&name, size),
                 HistoryLine::Directory(name) => file_system.add_dir(&name),
@@ -141,21 +159,22 @@ impl FromLines for Input {
             }
         }
 
-        Self {
-            file_system
-        }
+        Ok(Self {
+            file_system,
+            parameters: Parameters::default(),
+        })
     }
 }
 
-impl FromLine for HistoryLine {
-    fn from_line(line: &str) -> Self {
+impl TryFromLine for HistoryLine {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
         let parts: Vec<&str> = line.split(' ').collect();
         match parts[..] {
-            ["$", "ls"] => Self::Ls,
-            ["$", "cd", path] => Self::Cd(path.into()),
-            ["dir", name] => Self::Directory(name.into()),
-            [size, name] => Self::File(name.into(), usize::from_line(size)),
-            _ => panic!("{line} is not a valid history line")
+            ["$", "ls"] => Ok(Self::Ls),
+            ["$", "cd", path] => Ok(Self::Cd(path.into())),
+            ["dir", name] => Ok(Self::Directory(name.into())),
+            [size, name] => Ok(Self::File(name.into(), usize::try_from_line(size)?)),
+            _ => Err(ParseError::new(line, "history line is not valid"))
         }
     }
 }
\ No newline at end of file
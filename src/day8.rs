@@ -1,21 +1,34 @@
+use std::io;
 use std::iter::successors;
 
-use util::{FromChar, FromLines, read, run, Vec2};
+use crate::util::{Grid, ParseError, ParseResult, Solution, TryFromChar, TryFromLines, Vec2};
+use crate::util::image::{Exporter, Graymap};
 
-mod util;
+// Each tree is drawn as a square of this size.
+const EXPORT_SCALE: usize = 8;
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day8.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+pub struct Day8;
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+impl Solution for Day8 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
+
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
+
+    fn export(input: &Input, exporter: &mut Exporter) -> io::Result<()> {
+        input.export(exporter)
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     forest: Forest,
 }
 
@@ -27,6 +40,18 @@ impl Input {
     fn part_2(&self) -> usize {
         self.forest.best_scenic_score().unwrap_or(0)
     }
+
+    // Heatmaps of the tree heights, and of the scenic scores.
+    fn export(&self, exporter: &mut Exporter) -> io::Result<()> {
+        let trees = &self.forest.trees;
+        exporter.save("heights", &Graymap::heatmap(trees, |it| it.0 as f64).scale(EXPORT_SCALE))?;
+
+        let mut scores = Grid::new(trees.width(), trees.height(), 0.0);
+        for position in trees.positions() {
+            scores[position] = self.forest.tree_scenic_score(position) as f64;
+        }
+        exporter.save("scenic_scores", &Graymap::heatmap(&scores, |it| *it).scale(EXPORT_SCALE))
+    }
 }
 
 type Position = Vec2<usize>;
@@ -34,56 +59,44 @@ type Direction = Vec2<isize>;
 
 #[derive(Debug)]
 struct Forest {
-    trees: Vec<Vec<Tree>>,
-    width: usize,
-    height: usize,
+    trees: Grid<Tree>,
 }
 
 impl Forest {
     fn tree(&self, position: Position) -> Tree {
-        self.trees[position.y()][position.x()]
+        self.trees[position]
     }
 
     fn trees_in(&self, position: Position, direction: Direction) -> impl Iterator<Item=Tree> + '_ {
-        let is_in_bounds = |position: &Position| position.x() < self.width && position.y() < self.height;
-
-        successors((position + direction).filter(is_in_bounds), move |position| {
-            (*position + direction).filter(is_in_bounds)
-        }).map(|position| {
-            self.tree(position)
+        successors(position + direction, move |position| {
+            *position + direction
+        }).map_while(|position| {
+            self.trees.get(position).copied()
         })
     }
 
     fn is_tree_visible(&self, position: Position) -> bool {
         let tree = self.tree(position);
 
-        // Top
-        for other_tree in self.trees_in(position, vec2!(0, -1)) {
-            if other_tree >= tree { return false; }
-        }
-        // Bottom
-        for other_tree in self.trees_in(position, vec2!(0, 1)) {
-            if other_tree >= tree { return false; }
-        }
-        // Left
-        for other_tree in self.trees_in(position, vec2!(-1, 0)) {
-            if other_tree >= tree { return false; }
-        }
-        // Right
-        for other_tree in self.trees_in(position, vec2!(1, 0)) {
-            if other_tree >= tree { return false; }
-        }
+        // A tree is visible if all trees in at least one direction are smaller.
+        let is_visible_from = |direction: Direction| self.trees_in(position, direction).all(|other_tree| other_tree < tree);
 
-        true
+        is_visible_from(vec2!(0, -1)) // Top
+            || is_visible_from(vec2!(0, 1)) // Bottom
+            || is_visible_from(vec2!(-1, 0)) // Left
+            || is_visible_from(vec2!(1, 0)) // Right
     }
 
     fn count_visible(&self) -> usize {
+        let width = self.trees.width();
+        let height = self.trees.height();
+
         // All trees around the forest are visible. No need to count them.
-        let mut count = self.width * 2 + self.height * 2 - 4;
+        let mut count = width * 2 + height * 2 - 4;
 
         // Count other trees.
-        for x in 1..self.width - 1 {
-            for y in 1..self.height - 1 {
+        for x in 1..width - 1 {
+            for y in 1..height - 1 {
                 if self.is_tree_visible(vec2!(x, y)) { count += 1; }
             }
         }
@@ -122,54 +135,40 @@ impl Forest {
     }
 
     fn best_scenic_score(&self) -> Option<usize> {
-        let mut best = None;
-        for x in 0..self.width {
-            for y in 0..self.height {
-                let current = Some(self.tree_scenic_score(vec2!(x, y)));
-                if current > best { best = current; }
-            }
-        }
-        best
+        self.trees
+            .positions()
+            .map(|it| self.tree_scenic_score(it))
+            .max()
     }
 }
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 struct Tree(u8);
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let forest = Forest::from_lines(lines);
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let forest = Forest::try_from_lines(lines)?;
 
-        Self {
+        Ok(Self {
             forest
-        }
+        })
     }
 }
 
-impl FromLines for Forest {
-    fn from_lines(lines: &[&str]) -> Self {
-        let width = lines.get(0).map(|it| it.len()).unwrap_or(0);
-        let height = lines.len();
-        let mut trees = vec![vec![Tree(0); width]; height];
-
-        for (y, row) in lines.iter().enumerate() {
-            for (x, tree) in row.chars().enumerate() {
-                trees[x][y] = Tree::from_char(tree);
-            }
-        }
+impl TryFromLines for Forest {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let trees = Grid::try_from_lines(lines)?;
 
-        Self {
-            trees,
-            width,
-            height,
-        }
+        Ok(Self {
+            trees
+        })
     }
 }
 
-impl FromChar for Tree {
-    fn from_char(char: char) -> Self {
-        let value = char.to_digit(10).expect("tree value should between 0 and 9") as u8;
+impl TryFromChar for Tree {
+    fn try_from_char(char: char) -> ParseResult<Self> {
+        let value = char.to_digit(10).ok_or_else(|| ParseError::for_char(char, "tree value should be between 0 and 9"))? as u8;
 
-        Self(value)
+        Ok(Self(value))
     }
 }
\ No newline at end of file
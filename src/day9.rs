@@ -1,25 +1,48 @@
 use std::collections::HashSet;
+use std::io;
 
-use util::{FromLine, FromLines, read, run, Vec2};
-
-mod util;
+use crate::util::{ParseError, ParseResult, Solution, TryFromLine, TryFromLines, Vec2};
+use crate::util::stream::{StreamSolution, TryFromLineStream};
+use crate::util::viz::{Frame, Renderer};
 
 // Optimisation opportunity : instead of simulating every knot, only simulate the head and the tail.
 // Tail only has to move when distance to head is greater than the number of knots.
 // Still, it's way more fun to simulate every knot.
 
-fn main() {
-    let (t0, input) = run(|| read::<Input, _>("inputs/day9.txt"));
-    let (t1, p1) = run(|| input.part_1());
-    let (t2, p2) = run(|| input.part_2());
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn part_1(input: &Input) -> usize {
+        input.part_1()
+    }
 
-    println!("Part 1 : {}", p1);
-    println!("Part 2 : {}", p2);
-    println!("Time : {} ns", (t0 + t1 + t2).as_nanos());
+    fn part_2(input: &Input) -> usize {
+        input.part_2()
+    }
+
+    fn animate(input: &Input, renderer: &mut Renderer) -> io::Result<()> {
+        input.animate(renderer)
+    }
+}
+
+impl StreamSolution for Day9 {
+    type Stream = Ropes;
+
+    fn stream_part_1(stream: &Ropes) -> usize {
+        stream.short.visited_count()
+    }
+
+    fn stream_part_2(stream: &Ropes) -> usize {
+        stream.long.visited_count()
+    }
 }
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     motions: Vec<Motion>,
 }
 
@@ -39,6 +62,36 @@ impl Input {
         }
         rope.visited_count()
     }
+
+    // Rope of part 2, step by step.
+    fn animate(&self, renderer: &mut Renderer) -> io::Result<()> {
+        let mut rope = Rope::<10>::new();
+        renderer.render(&rope)?;
+        for motion in &self.motions {
+            for _ in 0..motion.length {
+                rope.step(motion.direction);
+                renderer.render(&rope)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Ropes of both parts, moved as the motions come. Motions are not kept, only the positions
+// visited by the tails.
+#[derive(Debug)]
+pub struct Ropes {
+    short: Rope<2>,
+    long: Rope<10>,
+}
+
+impl Default for Ropes {
+    fn default() -> Self {
+        Self {
+            short: Rope::new(),
+            long: Rope::new(),
+        }
+    }
 }
 
 type Position = Vec2<isize>;
@@ -61,31 +114,28 @@ impl<const N: usize> Rope<N> {
     }
 
     fn apply(&mut self, motion: &Motion) {
-        let length = motion.length;
-        let head_direction = motion.direction;
-        for _ in 0..length {
-            // Apply motion to head.
-            self.knots[0] += head_direction;
-
-            // Apply motion to following knots.
-            for i in 0..N - 1 {
-                let head = self.knots[i];
-                let tail = &mut self.knots[i + 1];
-
-                let distance_x = head.x() - tail.x();
-                let distance_y = head.y() - tail.y();
-                let distance = distance_x.abs().max(distance_y.abs());
-                if distance > 1 {
-                    let move_x = distance_x.clamp(-1, 1);
-                    let move_y = distance_y.clamp(-1, 1);
-                    let tail_direction = vec2!(move_x, move_y);
-                    *tail += tail_direction;
-                }
-            }
+        for _ in 0..motion.length {
+            self.step(motion.direction);
+        }
+    }
+
+    fn step(&mut self, head_direction: Direction) {
+        // Apply motion to head.
+        self.knots[0] += head_direction;
+
+        // Apply motion to following knots.
+        for i in 0..N - 1 {
+            let head = self.knots[i];
+            let tail = &mut self.knots[i + 1];
 
-            // Record position visited by tail.
-            self.visited.insert(self.knots[N - 1]);
+            // Tail follows the head, one step at a time, when they stop touching.
+            if head.chebyshev(*tail) > 1 {
+                *tail += (head - *tail).signum();
+            }
         }
+
+        // Record position visited by tail.
+        self.visited.insert(self.knots[N - 1]);
     }
 
     fn visited_count(&self) -> usize {
@@ -93,38 +143,75 @@ impl<const N: usize> Rope<N> {
     }
 }
 
+// Y points up for the rope, but down on screen. Flip it.
+impl<const N: usize> Frame for Rope<N> {
+    fn bounds(&self) -> (Position, Position) {
+        let positions = self.knots.iter().chain(&self.visited).map(|it| vec2!(it.x(), -it.y()));
+        let (min, max) = positions.fold((vec2!(0, 0), vec2!(0, 0)), |(min, max), it| {
+            (vec2!(min.x().min(it.x()), min.y().min(it.y())), vec2!(max.x().max(it.x()), max.y().max(it.y())))
+        });
+        (min, max)
+    }
+
+    fn cell(&self, position: Position) -> char {
+        let position = vec2!(position.x(), -position.y());
+        match self.knots.iter().position(|it| *it == position) {
+            Some(0) => 'H',
+            Some(i) => char::from_digit(i as u32, 36).unwrap_or('T'),
+            None if position == vec2!(0, 0) => 's',
+            None if self.visited.contains(&position) => '#',
+            None => '.',
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Motion {
     direction: Direction,
     length: usize,
 }
 
-impl FromLines for Input {
-    fn from_lines(lines: &[&str]) -> Self {
-        let commands = lines.iter().map(line_to!(Motion)).collect();
+impl TryFromLines for Input {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let motions = lines.iter().map(try_line_to!(Motion)).collect::<ParseResult<_>>()?;
 
-        Self {
-            motions: commands
-        }
+        Ok(Self {
+            motions
+        })
+    }
+}
+
+impl TryFromLineStream for Ropes {
+    type State = Self;
+
+    fn try_push_line(ropes: &mut Self, line: &str) -> ParseResult<()> {
+        let motion = Motion::try_from_line(line)?;
+        ropes.short.apply(&motion);
+        ropes.long.apply(&motion);
+        Ok(())
+    }
+
+    fn try_finish(ropes: Self) -> ParseResult<Self> {
+        Ok(ropes)
     }
 }
 
-impl FromLine for Motion {
-    fn from_line(line: &str) -> Self {
-        let (direction, length) = line.split_once(' ').expect("motions should have a direction and a length");
+impl TryFromLine for Motion {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let (direction, length) = line.split_once(' ').ok_or_else(|| ParseError::new(line, "motion should have a direction and a length"))?;
 
         let direction = match direction {
             "U" => vec2!(0, 1),
             "D" => vec2!(0, -1),
             "L" => vec2!(-1, 0),
             "R" => vec2!(1, 0),
-            _ => panic!("{direction} is not a valid direction")
+            _ => return Err(ParseError::new(direction, "direction is not valid"))
         };
-        let length = usize::from_line(length);
+        let length = usize::try_from_line(length)?;
 
-        Self {
+        Ok(Self {
             direction,
             length,
-        }
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use util::bench::Benchmark;
+use util::image::Exporter;
+use util::stream::StreamSolution;
+use util::viz::Renderer;
+use util::{Execution, OutputResult, Puzzle, ReadError, Solution};
+
+#[macro_use]
+pub mod util;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+// Runs a solution once on a puzzle, measuring each phase.
+pub type Execute = fn(&Puzzle) -> Result<Execution, ReadError>;
+
+// Type erased solution. Runs a solution on the given puzzle input.
+pub struct Runner {
+    pub execute: Execute,
+    pub benchmark: fn(&Puzzle, Duration) -> Result<Benchmark, ReadError>,
+    pub animate: fn(&Puzzle, &mut Renderer) -> OutputResult,
+    pub export: fn(&Puzzle, &mut Exporter) -> OutputResult,
+    // Same as execute, in constant memory. Only some line oriented solutions have one.
+    pub stream: Option<Execute>,
+    // Not written yet. Running it would panic.
+    pub is_stub: bool,
+}
+
+impl Runner {
+    const fn of<S>() -> Self
+        where S: Solution {
+        Self {
+            execute: util::execute::<S>,
+            benchmark: util::benchmark::<S>,
+            animate: util::animate::<S>,
+            export: util::export::<S>,
+            stream: None,
+            is_stub: false,
+        }
+    }
+
+    // Solutions which can also stream their input. Benchmarks also measure streaming, and check
+    // that it gives the same answers.
+    const fn streamed<S>() -> Self
+        where S: StreamSolution {
+        Self {
+            benchmark: util::benchmark_stream::<S>,
+            stream: Some(util::execute_stream::<S>),
+            ..Self::of::<S>()
+        }
+    }
+
+    // Solutions which are not written yet. They are skipped rather than run.
+    const fn stub<S>() -> Self
+        where S: Solution {
+        Self {
+            is_stub: true,
+            ..Self::of::<S>()
+        }
+    }
+}
+
+// Solutions, by day. Day 1 is at index 0.
+pub const SOLUTIONS: [Runner; 25] = [
+    Runner::streamed::<day1::Day1>(),
+    Runner::streamed::<day2::Day2>(),
+    Runner::of::<day3::Day3>(),
+    Runner::streamed::<day4::Day4>(),
+    Runner::of::<day5::Day5>(),
+    Runner::of::<day6::Day6>(),
+    Runner::of::<day7::Day7>(),
+    Runner::of::<day8::Day8>(),
+    Runner::streamed::<day9::Day9>(),
+    Runner::streamed::<day10::Day10>(),
+    Runner::of::<day11::Day11>(),
+    Runner::of::<day12::Day12>(),
+    Runner::of::<day13::Day13>(),
+    Runner::of::<day14::Day14>(),
+    Runner::of::<day15::Day15>(),
+    Runner::stub::<day16::Day16>(),
+    Runner::of::<day17::Day17>(),
+    Runner::of::<day18::Day18>(),
+    Runner::of::<day19::Day19>(),
+    Runner::of::<day20::Day20>(),
+    Runner::of::<day21::Day21>(),
+    Runner::stub::<day22::Day22>(),
+    Runner::stub::<day23::Day23>(),
+    Runner::stub::<day24::Day24>(),
+    Runner::stub::<day25::Day25>(),
+];
@@ -0,0 +1,551 @@
+use std::env;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use advent_of_code_2022::{Execute, SOLUTIONS};
+use advent_of_code_2022::util::answers::Answers;
+use advent_of_code_2022::util::parameters::Manifest;
+use advent_of_code_2022::util::summary::{self, Format, Job};
+use advent_of_code_2022::util::{read, Execution, Puzzle, ReadError, Source, Vec2};
+use advent_of_code_2022::util::history::Recorder;
+use advent_of_code_2022::util::image::Exporter;
+use advent_of_code_2022::util::viz::{Renderer, Viewport};
+
+const USAGE: &str = "Usage : aoc <COMMAND> <DAYS> [OPTIONS]
+
+Commands :
+  run     Run the solutions once and print the answers
+  bench   Run each phase of the solutions repeatedly and print timing statistics
+  summary Run the solutions in parallel and print a table of the answers, timings and status
+
+Where <DAYS> is either :
+  - a single day (ex. 7)
+  - a range of days (ex. 1..=16 or 1..17)
+  - all days (all)
+
+Options :
+  --budget <MS>    Time spent on each phase when benchmarking (default : 1000)
+  --check          Compare the answers with the known answers (inputs/answers.txt)
+  --input <PATH>   Read the input from a file instead of inputs/dayX.txt (- for stdin, single day only)
+  --stream         Stream the input line by line, in constant memory (days 1, 2, 4, 9 and 10, other days
+                   read the whole input)
+  --example        Run the puzzle examples (examples/dayX.txt, checked against examples/answers.txt,
+                   with the parameters of examples/parameters.txt)
+  --param <N>=<V>  Override a parameter of the puzzle (single day only)
+  --animate        Animate the simulation in the terminal before printing the answers (days 9, 10 and 14)
+  --fps <N>        Frames per second of the animation, 0 for as fast as possible (default : 30)
+  --viewport <X>,<Y>,<W>,<H>
+                   Part of the animation to draw (default : everything, cropped to 160x50)
+  --export <DIR>   Save images of the puzzles inside a directory (days 8, 12, 14 and 15)
+  --threads <N>    Threads running the solutions of the summary (default : one per CPU, always one when
+                   allocations are counted)
+  --sequential     Run the solutions of the summary one at a time, for cleaner timings
+  --format <F>     Print the results of run or summary as text, json or csv (default : text)
+  --history <PATH> Append the timings of run or bench to a history file, and fail on regressions
+  --threshold <P>  Slowdown, in percent, from which a phase is a regression (default : 10)";
+
+const DEFAULT_BUDGET: Duration = Duration::from_millis(1000);
+const DEFAULT_FPS: u32 = 30;
+const DEFAULT_THRESHOLD: f64 = 10.0;
+const ANSWERS_PATH: &str = "inputs/answers.txt";
+const EXAMPLE_ANSWERS_PATH: &str = "examples/answers.txt";
+const EXAMPLE_PARAMETERS_PATH: &str = "examples/parameters.txt";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args = match Args::parse(&args) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let success = match args.command {
+        Command::Run => run(&args),
+        Command::Bench => bench(&args),
+        Command::Summary => summary(&args),
+    };
+
+    if success { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Command {
+    Run,
+    Bench,
+    Summary,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    days: RangeInclusive<usize>,
+    budget: Duration,
+    check: bool,
+    input: Option<Source>,
+    stream: bool,
+    example: bool,
+    parameters: Vec<(String, String)>,
+    animate: bool,
+    fps: u32,
+    viewport: Option<Viewport>,
+    export: Option<PathBuf>,
+    threads: usize,
+    format: Format,
+    history: Option<PathBuf>,
+    threshold: f64,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut args = args.iter().map(|it| it.as_str());
+
+        let command = match args.next() {
+            Some("run") => Command::Run,
+            Some("bench") => Command::Bench,
+            Some("summary") => Command::Summary,
+            Some(command) => return Err(format!("\"{command}\" is not a valid command")),
+            None => return Err("a command should be provided".into()),
+        };
+
+        let days = args.next().ok_or("days should be provided")?;
+        let days = parse_days(days).ok_or_else(|| format!("\"{days}\" is not a valid day selection"))?;
+
+        let mut budget = DEFAULT_BUDGET;
+        let mut check = false;
+        let mut input = None;
+        let mut stream = false;
+        let mut example = false;
+        let mut parameters = Vec::new();
+        let mut animate = false;
+        let mut fps = DEFAULT_FPS;
+        let mut viewport = None;
+        let mut export = None;
+        let mut threads = thread::available_parallelism().map(|it| it.get()).unwrap_or(1);
+        let mut format = Format::Text;
+        let mut history = None;
+        let mut threshold = DEFAULT_THRESHOLD;
+        while let Some(option) = args.next() {
+            match option {
+                "--budget" => {
+                    let value = args.next().ok_or("--budget should have a value")?;
+                    let value = value.parse().map_err(|_| format!("\"{value}\" is not a valid budget"))?;
+                    budget = Duration::from_millis(value);
+                }
+                "--check" => check = true,
+                "--input" => {
+                    let value = args.next().ok_or("--input should have a value")?;
+                    input = Some(if value == "-" { Source::Stdin } else { Source::File(value.into()) });
+                }
+                "--stream" => stream = true,
+                "--example" => example = true,
+                "--param" => {
+                    let value = args.next().ok_or("--param should have a value")?;
+                    let (name, value) = value.split_once('=').ok_or_else(|| format!("\"{value}\" should be a name and a value (ex. rounds=20)"))?;
+                    parameters.push((name.into(), value.into()));
+                }
+                "--animate" => animate = true,
+                "--fps" => {
+                    let value = args.next().ok_or("--fps should have a value")?;
+                    fps = value.parse().map_err(|_| format!("\"{value}\" is not a valid frame rate"))?;
+                }
+                "--viewport" => {
+                    let value = args.next().ok_or("--viewport should have a value")?;
+                    viewport = Some(parse_viewport(value).ok_or_else(|| format!("\"{value}\" is not a valid viewport (ex. 0,0,80,25)"))?);
+                }
+                "--export" => {
+                    let value = args.next().ok_or("--export should have a value")?;
+                    export = Some(value.into());
+                }
+                "--threads" => {
+                    let value = args.next().ok_or("--threads should have a value")?;
+                    threads = value.parse().ok().filter(|it| *it > 0).ok_or_else(|| format!("\"{value}\" is not a valid thread count"))?;
+                }
+                "--sequential" => threads = 1,
+                "--format" => {
+                    format = match args.next().ok_or("--format should have a value")? {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        "csv" => Format::Csv,
+                        value => return Err(format!("\"{value}\" is not a valid format (text, json or csv)")),
+                    };
+                }
+                "--history" => {
+                    let value = args.next().ok_or("--history should have a value")?;
+                    history = Some(value.into());
+                }
+                "--threshold" => {
+                    let value = args.next().ok_or("--threshold should have a value")?;
+                    threshold = value.parse().ok().filter(|it: &f64| *it >= 0.0).ok_or_else(|| format!("\"{value}\" is not a valid threshold"))?;
+                }
+                option => return Err(format!("\"{option}\" is not a valid option")),
+            }
+        }
+
+        // The same input can't be used for many days.
+        if input.is_some() && days.clone().count() > 1 { return Err("--input should be used with a single day".into()); }
+        // Parameters are specific to a day.
+        if !parameters.is_empty() && days.clone().count() > 1 { return Err("--param should be used with a single day".into()); }
+        // Animations are drawn on stdout, which would be mixed with the answers of other commands.
+        // Benchmarks of streamed days already measure streaming.
+        if stream && matches!(command, Command::Bench) { return Err("--stream should be used with the run or summary command".into()); }
+        if animate && !matches!(command, Command::Run) { return Err("--animate should be used with the run command".into()); }
+        // The input is read once for the animation, and once for the answers.
+        if animate && matches!(input, Some(Source::Stdin)) { return Err("--animate can't be used with stdin".into()); }
+        if export.is_some() && !matches!(command, Command::Run) { return Err("--export should be used with the run command".into()); }
+        if export.is_some() && matches!(input, Some(Source::Stdin)) { return Err("--export can't be used with stdin".into()); }
+        // Anything else on stdout would break the output of other tools.
+        if format != Format::Text && matches!(command, Command::Bench) { return Err("--format should be used with the run or summary command".into()); }
+        if format != Format::Text && (animate || export.is_some()) { return Err("--format can't be used with --animate or --export".into()); }
+        // Timings of parallel runs can't be compared.
+        if history.is_some() && matches!(command, Command::Summary) { return Err("--history should be used with the run or bench command".into()); }
+
+        Ok(Self {
+            command,
+            days,
+            budget,
+            check,
+            input,
+            stream,
+            example,
+            parameters,
+            animate,
+            fps,
+            viewport,
+            export,
+            threads,
+            format,
+            history,
+            threshold,
+        })
+    }
+
+    // Parameters of the examples, or none.
+    fn manifest(&self) -> Result<Manifest, ReadError> {
+        if self.example { read(EXAMPLE_PARAMETERS_PATH) } else { Ok(Manifest::default()) }
+    }
+
+    fn puzzle(&self, day: usize, manifest: &Manifest) -> Puzzle {
+        let source = match &self.input {
+            Some(input) => input.clone(),
+            None if self.example => Source::File(format!("examples/day{day}.txt").into()),
+            None => Source::File(format!("inputs/day{day}.txt").into()),
+        };
+
+        // Command line parameters come last, to override the manifest.
+        Puzzle::new(source)
+            .parameters(manifest.day(day))
+            .parameters(&self.parameters)
+    }
+
+    fn jobs(&self, manifest: &Manifest) -> Vec<Job> {
+        self.days
+            .clone()
+            .map(|day| Job {
+                day,
+                puzzle: self.puzzle(day, manifest),
+                execute: self.execute(day),
+                is_stub: SOLUTIONS[day - 1].is_stub,
+            })
+            .collect()
+    }
+
+    // Streamed if asked, and if the day can be streamed.
+    fn execute(&self, day: usize) -> Execute {
+        let runner = &SOLUTIONS[day - 1];
+        runner.stream.filter(|_| self.stream).unwrap_or(runner.execute)
+    }
+
+    // Timings history, if one is kept.
+    fn recorder(&self) -> Result<Option<Recorder>, ReadError> {
+        // Streamed runs solve while parsing, so their phases are not comparable with other runs.
+        let command = match self.command {
+            Command::Run if self.stream => "stream",
+            Command::Run => "run",
+            Command::Bench => "bench",
+            Command::Summary => "summary",
+        };
+        self.history.as_ref().map(|it| Recorder::open(it, command, self.threshold)).transpose()
+    }
+
+    fn answers_path(&self) -> &'static str {
+        if self.example { EXAMPLE_ANSWERS_PATH } else { ANSWERS_PATH }
+    }
+}
+
+fn run(args: &Args) -> bool {
+    let count = args.days.clone().count();
+
+    let manifest = match args.manifest() {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            return false;
+        }
+    };
+
+    let answers = if args.check {
+        match read::<Answers, _>(args.answers_path()) {
+            Ok(answers) => Some(answers),
+            Err(error) => {
+                eprintln!("Error : {error}");
+                return false;
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut recorder = match args.recorder() {
+        Ok(recorder) => recorder,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            return false;
+        }
+    };
+
+    // Other tools get the same results as the summary, run one day at a time.
+    if args.format != Format::Text {
+        let summary = summary::run(args.jobs(&manifest), 1, answers.as_ref());
+        print!("{}", summary.format(args.format));
+
+        let mut success = summary.is_success();
+        for row in &summary.rows {
+            if let Some(execution) = &row.execution {
+                if !record(&mut recorder, row.day, &args.puzzle(row.day, &manifest), &execution_phases(execution)) { success = false; }
+            }
+        }
+        return save(recorder) && success;
+    }
+
+    let mut success = true;
+    let mut total = Duration::ZERO;
+    for day in args.days.clone() {
+        // Stubs would panic. They are expected, so they are not failures.
+        if SOLUTIONS[day - 1].is_stub {
+            println!("Day {day}\nNot implemented\n");
+            continue;
+        }
+
+        let puzzle = args.puzzle(day, &manifest);
+        if args.animate && !animate(args, day, &puzzle) { success = false; }
+
+        println!("Day {day}");
+        match args.execute(day)(&puzzle) {
+            Ok(execution) => {
+                print_answer("Part 1", &execution.part_1);
+                print_answer("Part 2", &execution.part_2);
+                println!("Time : {} ns", execution.time().as_nanos());
+                print_allocations(&execution);
+
+                if let Some(answers) = &answers {
+                    let (part_1, part_2) = answers.check(day, &execution.part_1, &execution.part_2);
+                    println!("Check part 1 : {part_1}");
+                    println!("Check part 2 : {part_2}");
+
+                    if part_1.is_fail() || part_2.is_fail() { success = false; }
+                }
+
+                if let Some(directory) = &args.export {
+                    if !export(directory, day, &puzzle) { success = false; }
+                }
+
+                if !record(&mut recorder, day, &puzzle, &execution_phases(&execution)) { success = false; }
+
+                total += execution.time();
+            }
+            Err(error) => {
+                eprintln!("Error : {error}");
+                success = false;
+            }
+        }
+        println!();
+    }
+
+    if count > 1 {
+        println!("Total time : {} ns", total.as_nanos());
+    }
+    save(recorder) && success
+}
+
+fn execution_phases(execution: &Execution) -> [(&'static str, Duration); 3] {
+    [("parse", execution.parse_time), ("part_1", execution.part_1_time), ("part_2", execution.part_2_time)]
+}
+
+// Regressions are errors, so the command can be used as a gate.
+fn record(recorder: &mut Option<Recorder>, day: usize, puzzle: &Puzzle, phases: &[(&str, Duration)]) -> bool {
+    let Some(recorder) = recorder else { return true; };
+
+    let mut success = true;
+    for (phase, time) in phases {
+        if let Some(regression) = recorder.record(day, puzzle, phase, *time) {
+            eprintln!("Regression : {regression}");
+            success = false;
+        }
+    }
+    success
+}
+
+fn save(recorder: Option<Recorder>) -> bool {
+    let Some(recorder) = recorder else { return true; };
+
+    let path = recorder.path().to_path_buf();
+    match recorder.save() {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("Error : {} : {error}", path.display());
+            false
+        }
+    }
+}
+
+fn animate(args: &Args, day: usize, puzzle: &Puzzle) -> bool {
+    let mut renderer = Renderer::stdout().fps(args.fps).viewport(args.viewport);
+    match (SOLUTIONS[day - 1].animate)(puzzle, &mut renderer) {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            false
+        }
+    }
+}
+
+// Images are named after the day, so all days can share the directory.
+fn export(directory: &PathBuf, day: usize, puzzle: &Puzzle) -> bool {
+    let mut exporter = Exporter::new(directory, format!("day{day}"));
+    let result = (SOLUTIONS[day - 1].export)(puzzle, &mut exporter);
+
+    // Some images may have been saved before an error.
+    for file in exporter.files() {
+        println!("Export : {}", file.display());
+    }
+    match result {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            false
+        }
+    }
+}
+
+fn summary(args: &Args) -> bool {
+    let manifest = match args.manifest() {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            return false;
+        }
+    };
+
+    let answers = if args.check {
+        match read::<Answers, _>(args.answers_path()) {
+            Ok(answers) => Some(answers),
+            Err(error) => {
+                eprintln!("Error : {error}");
+                return false;
+            }
+        }
+    } else {
+        None
+    };
+
+    let summary = summary::run(args.jobs(&manifest), args.threads, answers.as_ref());
+    print!("{}", summary.format(args.format));
+    summary.is_success()
+}
+
+fn bench(args: &Args) -> bool {
+    let manifest = match args.manifest() {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            return false;
+        }
+    };
+
+    let mut recorder = match args.recorder() {
+        Ok(recorder) => recorder,
+        Err(error) => {
+            eprintln!("Error : {error}");
+            return false;
+        }
+    };
+
+    let mut success = true;
+    for day in args.days.clone() {
+        if SOLUTIONS[day - 1].is_stub {
+            println!("Day {day}\nNot implemented\n");
+            continue;
+        }
+
+        println!("Day {day}");
+        let puzzle = args.puzzle(day, &manifest);
+        match (SOLUTIONS[day - 1].benchmark)(&puzzle, args.budget) {
+            Ok(benchmark) => {
+                print!("{benchmark}");
+
+                // Medians are the least sensitive to outliers.
+                let mut phases = vec![("parse", benchmark.parse.median), ("part_1", benchmark.part_1.median), ("part_2", benchmark.part_2.median)];
+                if let Some(stream) = benchmark.stream { phases.push(("stream", stream.median)); }
+                if !record(&mut recorder, day, &puzzle, &phases) { success = false; }
+            }
+            Err(error) => {
+                eprintln!("Error : {error}");
+                success = false;
+            }
+        }
+        println!();
+    }
+    save(recorder) && success
+}
+
+fn print_allocations(execution: &Execution) {
+    let phases = [("parse", execution.parse_allocations), ("part 1", execution.part_1_allocations), ("part 2", execution.part_2_allocations)];
+    for (name, allocations) in phases.into_iter().filter_map(|(name, allocations)| Some((name, allocations?))) {
+        println!("Allocations {name} : {allocations}");
+    }
+}
+
+fn print_answer(name: &str, answer: &str) {
+    // Multiline answers (like drawings) start on their own line.
+    if answer.contains('\n') {
+        println!("{name} :\n{answer}");
+    } else {
+        println!("{name} : {answer}");
+    }
+}
+
+fn parse_days(days: &str) -> Option<RangeInclusive<usize>> {
+    let parse_day = |day: &str| day.parse::<usize>().ok();
+
+    let range = if days == "all" {
+        1..=SOLUTIONS.len()
+    } else if let Some((start, end)) = days.split_once("..=") {
+        parse_day(start)?..=parse_day(end)?
+    } else if let Some((start, end)) = days.split_once("..") {
+        // The end is exclusive, so 1..26 is every day.
+        parse_day(start)?..=parse_day(end)?.checked_sub(1)?
+    } else {
+        let day = parse_day(days)?;
+        day..=day
+    };
+
+    let days = 1..=SOLUTIONS.len();
+    Some(range).filter(|it| !it.is_empty() && days.contains(it.start()) && days.contains(it.end()))
+}
+
+fn parse_viewport(viewport: &str) -> Option<Viewport> {
+    let values: Vec<&str> = viewport.split(',').collect();
+    match values[..] {
+        [x, y, width, height] => Some(Viewport::new(
+            Vec2::new(x.parse().ok()?, y.parse().ok()?),
+            width.parse().ok()?,
+            height.parse().ok()?,
+        )),
+        _ => None,
+    }
+}
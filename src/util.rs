@@ -1,27 +1,318 @@
 use std::any::type_name;
-use std::fmt::Debug;
-use std::fs;
-use std::ops::{Add, AddAssign};
-use std::path::Path;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::num::TryFromIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 
-pub fn read<T, P>(path: P) -> T
-    where T: FromLines,
+use alloc::Allocations;
+use bench::Benchmark;
+use stream::{read_stream, StreamSolution, TimedReader, TryFromLineStream};
+use image::Exporter;
+use viz::Renderer;
+use parameters::Parameters;
+pub use grid::Grid;
+
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod grid;
+pub mod history;
+pub mod image;
+pub mod interval;
+#[macro_use]
+pub mod parameters;
+pub mod scan;
+pub mod search;
+pub mod stream;
+pub mod summary;
+pub mod viz;
+
+pub fn read<T, P>(path: P) -> Result<T, ReadError>
+    where T: TryFromLines,
           P: AsRef<Path> {
-    let file = fs::read_to_string(path).expect("input should be readable");
+    let path = path.as_ref();
+    let file = read_file(path)?;
+    let lines: Vec<&str> = file.lines().collect();
+    parse(path, &file, &lines)
+}
+
+fn read_file(path: &Path) -> Result<String, ReadError> {
+    fs::read_to_string(path).map_err(|it| ReadError::Io(path.into(), it))
+}
+
+fn read_source(source: &Source) -> Result<String, ReadError> {
+    match source {
+        Source::File(path) => read_file(path),
+        Source::Stdin => io::read_to_string(io::stdin()).map_err(|it| ReadError::Io(source.path().into(), it)),
+    }
+}
+
+fn open_source(source: &Source) -> Result<Box<dyn Read>, ReadError> {
+    match source {
+        Source::File(path) => Ok(Box::new(File::open(path).map_err(|it| ReadError::Io(path.into(), it))?)),
+        Source::Stdin => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+fn parse<T>(path: &Path, file: &str, lines: &[&str]) -> Result<T, ReadError>
+    where T: TryFromLines {
+    T::try_from_lines(lines).map_err(|it| ReadError::Parse(it.locate(path, file)))
+}
+
+// Either the input can't be read, or the output (animation, images) can't be written.
+pub type OutputResult = Result<(), Box<dyn Error>>;
+
+pub fn animate<S>(puzzle: &Puzzle, renderer: &mut Renderer) -> OutputResult
+    where S: Solution {
+    let input = read_puzzle::<S>(puzzle)?;
+
+    S::animate(&input, renderer)?;
+    renderer.finish()?;
+    Ok(())
+}
+
+pub fn export<S>(puzzle: &Puzzle, exporter: &mut Exporter) -> OutputResult
+    where S: Solution {
+    let input = read_puzzle::<S>(puzzle)?;
+
+    S::export(&input, exporter)?;
+    Ok(())
+}
+
+fn read_puzzle<S>(puzzle: &Puzzle) -> Result<S::Input, ReadError>
+    where S: Solution {
+    let file = read_source(&puzzle.source)?;
+    let lines: Vec<&str> = file.lines().collect();
+    puzzle.prepare::<S>(parse(puzzle.source.path(), &file, &lines)?)
+}
+
+// Reading the input is not part of the measurements. Stdin would mostly measure the pipe.
+pub fn execute<S>(puzzle: &Puzzle) -> Result<Execution, ReadError>
+    where S: Solution {
+    let path = puzzle.source.path();
+    let file = read_source(&puzzle.source)?;
+    let lines: Vec<&str> = file.lines().collect();
+
+    let (parse_allocations, (parse_time, input)) = alloc::count(|| run(|| puzzle.prepare::<S>(parse(path, &file, &lines)?)));
+    let input = input?;
+    let (part_1_allocations, (part_1_time, part_1)) = alloc::count(|| run(|| S::part_1(&input)));
+    let (part_2_allocations, (part_2_time, part_2)) = alloc::count(|| run(|| S::part_2(&input)));
+
+    Ok(Execution {
+        part_1: part_1.to_string(),
+        part_2: part_2.to_string(),
+        parse_time,
+        part_1_time,
+        part_2_time,
+        parse_allocations,
+        part_1_allocations,
+        part_2_allocations,
+    })
+}
+
+// Same as execute, but the input is streamed, in constant memory. Reading happens along the
+// parsing, so the time spent reading is taken out of it. The lines are folded while parsing,
+// so most of the solving is part of the parse time.
+pub fn execute_stream<S>(puzzle: &Puzzle) -> Result<Execution, ReadError>
+    where S: StreamSolution {
+    let path = puzzle.source.path();
+    let mut reader = TimedReader::new(open_source(&puzzle.source)?);
+
+    let (parse_allocations, (parse_time, stream)) = alloc::count(|| run(|| read_stream(path, &mut reader, puzzle.stream_state::<S>()?)));
+    let parse_time = parse_time.saturating_sub(reader.time());
+    let stream = stream?;
+    let (part_1_allocations, (part_1_time, part_1)) = alloc::count(|| run(|| S::stream_part_1(&stream)));
+    let (part_2_allocations, (part_2_time, part_2)) = alloc::count(|| run(|| S::stream_part_2(&stream)));
+
+    Ok(Execution {
+        part_1: part_1.to_string(),
+        part_2: part_2.to_string(),
+        parse_time,
+        part_1_time,
+        part_2_time,
+        parse_allocations,
+        part_1_allocations,
+        part_2_allocations,
+    })
+}
+
+// Measure each phase separately, spending the time budget on each of them.
+// Reading the file is not part of the measurements.
+pub fn benchmark<S>(puzzle: &Puzzle, budget: Duration) -> Result<Benchmark, ReadError>
+    where S: Solution {
+    let file = read_source(&puzzle.source)?;
+    measure_phases::<S>(puzzle, &file, budget)
+}
+
+fn measure_phases<S>(puzzle: &Puzzle, file: &str, budget: Duration) -> Result<Benchmark, ReadError>
+    where S: Solution {
+    let lines: Vec<&str> = file.lines().collect();
+    let input = puzzle.prepare::<S>(parse(puzzle.source.path(), file, &lines)?)?;
+
+    Ok(Benchmark {
+        parse: bench::measure(budget, || S::Input::try_from_lines(&lines).map(|it| puzzle.prepare::<S>(it))),
+        part_1: bench::measure(budget, || S::part_1(&input)),
+        part_2: bench::measure(budget, || S::part_2(&input)),
+        stream: None,
+    })
+}
+
+// Same as benchmark, but also measures streaming the input, from memory. Streaming should give
+// the same answers as the usual input.
+pub fn benchmark_stream<S>(puzzle: &Puzzle, budget: Duration) -> Result<Benchmark, ReadError>
+    where S: StreamSolution {
+    let path = puzzle.source.path();
+    let file = read_source(&puzzle.source)?;
     let lines: Vec<&str> = file.lines().collect();
-    T::from_lines(&lines)
+    let stream = || read_stream::<S::Stream, _>(path, file.as_bytes(), puzzle.stream_state::<S>()?);
+
+    let input = puzzle.prepare::<S>(parse(path, &file, &lines)?)?;
+    let streamed = stream()?;
+    let answers = (S::part_1(&input).to_string(), S::part_2(&input).to_string());
+    let streamed_answers = (S::stream_part_1(&streamed).to_string(), S::stream_part_2(&streamed).to_string());
+    if answers != streamed_answers {
+        let error = ParseError::missing(format!("streamed input should give the same answers ({answers:?} != {streamed_answers:?})"));
+        return Err(ReadError::Parse(error.locate(path, "")));
+    }
+
+    Ok(Benchmark {
+        stream: Some(bench::measure(budget, stream)),
+        ..measure_phases::<S>(puzzle, &file, budget)?
+    })
+}
+
+// Input of a solution : where it comes from, and which parameters are overridden.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub source: Source,
+    pub parameters: Vec<(String, String)>,
+}
+
+impl Puzzle {
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            parameters: Vec::new(),
+        }
+    }
+
+    // Override parameters, by name. Last one wins.
+    pub fn parameters<'a>(mut self, parameters: impl IntoIterator<Item=&'a (String, String)>) -> Self {
+        self.parameters.extend(parameters.into_iter().cloned());
+        self
+    }
+
+    fn prepare<S>(&self, mut input: S::Input) -> Result<S::Input, ReadError>
+        where S: Solution {
+        self.configure(S::parameters(&mut input))?;
+        Ok(input)
+    }
+
+    // Streamed inputs get their parameters before the first line.
+    fn stream_state<S>(&self) -> Result<<S::Stream as TryFromLineStream>::State, ReadError>
+        where S: StreamSolution {
+        let mut state = Default::default();
+        self.configure(S::Stream::parameters(&mut state))?;
+        Ok(state)
+    }
+
+    // Override the parameters, if there are any to override.
+    fn configure(&self, parameters: Option<&mut dyn Parameters>) -> Result<(), ReadError> {
+        if let Some((name, _)) = self.parameters.first() {
+            let parameters = parameters.ok_or_else(|| ReadError::Parameter(ParseError::new(name, "parameter does not exist")))?;
+            for (name, value) in &self.parameters {
+                parameters.set(name, value).map_err(ReadError::Parameter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    // Path used in errors.
+    pub fn path(&self) -> &Path {
+        match self {
+            Source::File(path) => path,
+            Source::Stdin => Path::new("<stdin>"),
+        }
+    }
 }
 
 pub fn run<T, F>(operation: F) -> (Duration, T)
-    where F: Fn() -> T {
+    where F: FnOnce() -> T {
     let start = Instant::now();
     let value = operation();
     let end = Instant::now();
     (end - start, value)
 }
 
+pub trait Solution {
+    type Input: TryFromLines;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn part_1(input: &Self::Input) -> Self::Part1;
+    fn part_2(input: &Self::Input) -> Self::Part2;
+
+    // Named constants of the puzzle, if any. Examples often use different ones.
+    fn parameters(_input: &mut Self::Input) -> Option<&mut dyn Parameters> {
+        None
+    }
+
+    // Replay the simulation of the puzzle in the terminal. Only some puzzles have one.
+    fn animate(_input: &Self::Input, _renderer: &mut Renderer) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "solution should have an animation"))
+    }
+
+    // Save images of the puzzle state. Only some puzzles have them.
+    fn export(_input: &Self::Input, _exporter: &mut Exporter) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "solution should have images to export"))
+    }
+}
+
+#[derive(Debug)]
+pub struct Execution {
+    pub part_1: String,
+    pub part_2: String,
+    pub parse_time: Duration,
+    pub part_1_time: Duration,
+    pub part_2_time: Duration,
+    // Only when allocations are counted.
+    pub parse_allocations: Option<Allocations>,
+    pub part_1_allocations: Option<Allocations>,
+    pub part_2_allocations: Option<Allocations>,
+}
+
+impl Execution {
+    pub fn time(&self) -> Duration {
+        self.parse_time + self.part_1_time + self.part_2_time
+    }
+
+    // Allocations of all phases, if they are counted. Peak is the highest of the phases.
+    pub fn allocations(&self) -> Option<Allocations> {
+        [self.parse_allocations, self.part_1_allocations, self.part_2_allocations]
+            .into_iter()
+            .try_fold(Allocations::default(), |total, it| {
+                it.map(|it| Allocations {
+                    count: total.count + it.count,
+                    bytes: total.bytes + it.bytes,
+                    peak: total.peak.max(it.peak),
+                })
+            })
+    }
+}
+
 pub trait FromChar {
     fn from_char(char: char) -> Self;
 }
@@ -34,14 +325,179 @@ pub trait FromLines {
     fn from_lines(lines: &[&str]) -> Self;
 }
 
+pub trait TryFromChar: Sized {
+    fn try_from_char(char: char) -> ParseResult<Self>;
+}
+
+pub trait TryFromLine: Sized {
+    fn try_from_line(line: &str) -> ParseResult<Self>;
+}
+
+pub trait TryFromLines: Sized {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self>;
+}
+
+impl<T> FromChar for T
+    where T: TryFromChar {
+    fn from_char(char: char) -> Self {
+        T::try_from_char(char).unwrap_or_else(|it| panic!("{it}"))
+    }
+}
+
 impl<T> FromLine for T
-    where T: FromStr,
-          <T as FromStr>::Err: Debug {
+    where T: TryFromLine {
     fn from_line(line: &str) -> Self {
-        T::from_str(line).expect(&format!("line should be a valid {}", type_name::<T>()))
+        T::try_from_line(line).unwrap_or_else(|it| panic!("{it}"))
+    }
+}
+
+impl<T> FromLines for T
+    where T: TryFromLines {
+    fn from_lines(lines: &[&str]) -> Self {
+        T::try_from_lines(lines).unwrap_or_else(|it| panic!("{it}"))
+    }
+}
+
+impl<T> TryFromLine for T
+    where T: FromStr,
+          <T as FromStr>::Err: Display {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        T::from_str(line).map_err(|it| ParseError::new(line, format!("line should be a valid {} ({it})", type_name::<T>())))
+    }
+}
+
+// Parses every char of a line. Errors are located at the offending char.
+pub fn chars_to<T>(line: &str) -> impl Iterator<Item=ParseResult<T>> + '_
+    where T: TryFromChar {
+    line.char_indices().map(|(i, char)| {
+        T::try_from_char(char).map_err(|it| it.at(&line[i..i + char.len_utf8()]))
+    })
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    message: String,
+    text: String,
+    // Address of the offending text. Since lines are slices of the input file, this is
+    // enough to find back the line and column where the error happened.
+    address: Option<usize>,
+    location: Option<Location>,
+}
+
+#[derive(Debug, Clone)]
+struct Location {
+    file: PathBuf,
+    // Line and column, if the offending text was found.
+    line: Option<(usize, usize)>,
+}
+
+impl ParseError {
+    pub fn new(text: &str, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            text: text.into(),
+            address: Some(text.as_ptr() as usize),
+            location: None,
+        }
+    }
+
+    pub fn for_char(char: char, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            text: char.into(),
+            address: None,
+            location: None,
+        }
+    }
+
+    pub fn missing(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            text: String::new(),
+            address: None,
+            location: None,
+        }
+    }
+
+    // Set the offending text, if the error was created without one (ex. from a single char).
+    pub fn at(mut self, text: &str) -> Self {
+        if self.address.is_none() {
+            self.address = Some(text.as_ptr() as usize);
+        }
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    // Find the line and column (both 1-based) of the offending text inside the input file.
+    fn locate(mut self, path: &Path, file: &str) -> Self {
+        let start = file.as_ptr() as usize;
+        let offset = self.address
+            .and_then(|it| it.checked_sub(start))
+            .filter(|it| *it <= file.len() && file.is_char_boundary(*it))
+            // The address alone could point inside the file by chance, if the text was sliced
+            // from another buffer.
+            .filter(|it| file[*it..].starts_with(&self.text));
+
+        let line = offset.map(|offset| {
+            let before = &file[..offset];
+            let line_start = before.rfind('\n').map(|it| it + 1).unwrap_or(0);
+
+            (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+        });
+
+        self.location = Some(Location {
+            file: path.into(),
+            line,
+        });
+        self
     }
 }
 
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(Location { file, line: Some((line, column)) }) => write!(f, "{}:{line}:{column} : ", file.display())?,
+            Some(Location { file, line: None }) => write!(f, "{} : ", file.display())?,
+            None => {}
+        }
+        write!(f, "{}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, " (found \"{}\")", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(PathBuf, io::Error),
+    Parse(ParseError),
+    Parameter(ParseError),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(path, error) => write!(f, "{} : {error}", path.display()),
+            ReadError::Parse(error) => write!(f, "{error}"),
+            ReadError::Parameter(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for ReadError {}
+
 #[macro_export]
 macro_rules! is_empty {
     () => {
@@ -78,6 +534,41 @@ macro_rules! lines_to {
     }
 }
 
+#[macro_export]
+macro_rules! try_char_to {
+    ($type:ty) => {
+        |it| <$type>::try_from_char(it)
+    }
+}
+
+#[macro_export]
+macro_rules! try_line_to {
+    ($type:ty) => {
+        |it| <$type>::try_from_line(it)
+    }
+}
+
+#[macro_export]
+macro_rules! try_lines_to {
+    ($type:ty) => {
+        |it| <$type>::try_from_lines(it)
+    }
+}
+
+// Parse a line with a pattern, where each "{}" is a value of the given types, in order.
+// Returns a tuple of the values. See scan() for the matching rules.
+//
+//    let (count, source, destination) = parse!(line, "move {} from {} to {}", usize, usize, usize)?;
+#[macro_export]
+macro_rules! parse {
+    ($line:expr, $pattern:literal $(, $type:ty)* $(,)?) => {
+        $crate::util::scan::scan($line, $pattern, [$(stringify!($type)),*].len()).and_then(|captures| {
+            let mut captures = captures.into_iter();
+            Ok(($(<$type as $crate::util::TryFromLine>::try_from_line(captures.next().expect("capture should exist for each type"))?,)*))
+        })
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 #[allow(unused)]
 pub struct Vec2<T>(T, T);
@@ -98,19 +589,113 @@ impl<T> Vec2<T>
     }
 }
 
-impl Add for Vec2<usize> {
-    type Output = Self;
+macro_rules! impl_vec2 {
+    ($($type:ty),*) => {$(
+        impl Add for Vec2<$type> {
+            type Output = Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
-    }
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0, self.1 + rhs.1)
+            }
+        }
+
+        impl AddAssign for Vec2<$type> {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+                self.1 += rhs.1;
+            }
+        }
+
+        impl Sub for Vec2<$type> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0, self.1 - rhs.1)
+            }
+        }
+
+        impl SubAssign for Vec2<$type> {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+                self.1 -= rhs.1;
+            }
+        }
+
+        impl Mul<$type> for Vec2<$type> {
+            type Output = Self;
+
+            fn mul(self, rhs: $type) -> Self::Output {
+                Self(self.0 * rhs, self.1 * rhs)
+            }
+        }
+    )*}
+}
+
+macro_rules! impl_vec2_signed {
+    ($($type:ty),*) => {$(
+        impl Vec2<$type> {
+            // Each component becomes -1, 0 or 1. Turns a difference into a single step.
+            pub fn signum(&self) -> Self {
+                Self(self.0.signum(), self.1.signum())
+            }
+
+            pub fn abs(&self) -> Self {
+                Self(self.0.abs(), self.1.abs())
+            }
+
+            pub fn manhattan(&self, other: Self) -> $type {
+                let distance = (*self - other).abs();
+                distance.0 + distance.1
+            }
+
+            pub fn chebyshev(&self, other: Self) -> $type {
+                let distance = (*self - other).abs();
+                distance.0.max(distance.1)
+            }
+
+            // Rotations assume Y points down, like in a grid.
+            pub fn rotate_clockwise(&self) -> Self {
+                Self(-self.1, self.0)
+            }
+
+            pub fn rotate_counterclockwise(&self) -> Self {
+                Self(self.1, -self.0)
+            }
+        }
+
+        impl Neg for Vec2<$type> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(-self.0, -self.1)
+            }
+        }
+    )*}
+}
+
+macro_rules! impl_vec2_try_from {
+    ($($from:ty => $to:ty),*) => {$(
+        impl TryFrom<Vec2<$from>> for Vec2<$to> {
+            type Error = TryFromIntError;
+
+            fn try_from(value: Vec2<$from>) -> Result<Self, Self::Error> {
+                Ok(Self(value.0.try_into()?, value.1.try_into()?))
+            }
+        }
+    )*}
 }
 
-impl Add for Vec2<isize> {
-    type Output = Self;
+impl_vec2!(usize, isize, i64);
+impl_vec2_signed!(isize, i64);
+impl_vec2_try_from!(usize => isize, usize => i64, isize => usize, isize => i64, i64 => usize, i64 => isize);
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
+impl Vec2<usize> {
+    pub fn manhattan(&self, other: Self) -> usize {
+        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
+    }
+
+    pub fn chebyshev(&self, other: Self) -> usize {
+        self.0.abs_diff(other.0).max(self.1.abs_diff(other.1))
     }
 }
 
@@ -118,7 +703,7 @@ impl Add<Vec2<isize>> for Vec2<usize> {
     type Output = Option<Self>;
 
     fn add(self, rhs: Vec2<isize>) -> Self::Output {
-        match (checked_add_signed(self.0, rhs.0), checked_add_signed(self.1, rhs.1)) {
+        match (self.0.checked_add_signed(rhs.0), self.1.checked_add_signed(rhs.1)) {
             (Some(x), Some(y)) => Some(Self(x, y)),
             _ => None
         }
@@ -129,55 +714,401 @@ impl Add<Vec2<usize>> for Vec2<isize> {
     type Output = Option<Self>;
 
     fn add(self, rhs: Vec2<usize>) -> Self::Output {
-        match (checked_add_unsigned(self.0, rhs.0), checked_add_unsigned(self.1, rhs.1)) {
+        match (self.0.checked_add_unsigned(rhs.0), self.1.checked_add_unsigned(rhs.1)) {
             (Some(x), Some(y)) => Some(Self(x, y)),
             _ => None
         }
     }
 }
 
-impl AddAssign for Vec2<usize> {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
+impl<T> TryFromLine for Vec2<T>
+    where T: TryFromLine {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        // Either "x,y" or "x=x, y=y".
+        let (lhs, rhs) = line.split_once(',').ok_or_else(|| ParseError::new(line, "vec2 should have a left and a right part"))?;
+        let lhs = lhs.trim();
+        let rhs = rhs.trim();
+        let lhs = lhs.strip_prefix("x=").unwrap_or(lhs);
+        let rhs = rhs.strip_prefix("y=").unwrap_or(rhs);
+        Ok(Self(T::try_from_line(lhs)?, T::try_from_line(rhs)?))
     }
 }
 
-impl AddAssign for Vec2<isize> {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
+#[macro_export]
+macro_rules! vec2 {
+    ($x:expr, $y:expr) => {
+        Vec2::new($x, $y)
     }
 }
 
-impl<T> FromLine for Vec2<T>
-    where T: FromLine {
-    fn from_line(line: &str) -> Self {
-        let (lhs, rhs) = line.split_once(',').expect("vec2 should have a left and a right part");
-        Self(T::from_line(lhs), T::from_line(rhs))
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct Vec3<T>(T, T, T);
+
+impl<T> Vec3<T>
+    where T: Copy {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self(x, y, z)
+    }
+
+    pub const fn x(&self) -> T {
+        self.0
+    }
+
+    pub const fn y(&self) -> T {
+        self.1
+    }
+
+    pub const fn z(&self) -> T {
+        self.2
+    }
+}
+
+macro_rules! impl_vec3 {
+    ($($type:ty),*) => {$(
+        impl Add for Vec3<$type> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+            }
+        }
+
+        impl AddAssign for Vec3<$type> {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+                self.1 += rhs.1;
+                self.2 += rhs.2;
+            }
+        }
+
+        impl Sub for Vec3<$type> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+            }
+        }
+
+        impl SubAssign for Vec3<$type> {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+                self.1 -= rhs.1;
+                self.2 -= rhs.2;
+            }
+        }
+
+        impl Mul<$type> for Vec3<$type> {
+            type Output = Self;
+
+            fn mul(self, rhs: $type) -> Self::Output {
+                Self(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+            }
+        }
+    )*}
+}
+
+macro_rules! impl_vec3_signed {
+    ($($type:ty),*) => {$(
+        impl Vec3<$type> {
+            pub fn signum(&self) -> Self {
+                Self(self.0.signum(), self.1.signum(), self.2.signum())
+            }
+
+            pub fn abs(&self) -> Self {
+                Self(self.0.abs(), self.1.abs(), self.2.abs())
+            }
+
+            pub fn manhattan(&self, other: Self) -> $type {
+                let distance = (*self - other).abs();
+                distance.0 + distance.1 + distance.2
+            }
+
+            pub fn chebyshev(&self, other: Self) -> $type {
+                let distance = (*self - other).abs();
+                distance.0.max(distance.1).max(distance.2)
+            }
+
+            // Neighbours sharing a face.
+            pub fn neighbours_6(&self) -> impl Iterator<Item=Self> {
+                let position = *self;
+                [
+                    Self(-1, 0, 0), Self(1, 0, 0),
+                    Self(0, -1, 0), Self(0, 1, 0),
+                    Self(0, 0, -1), Self(0, 0, 1),
+                ].into_iter().map(move |it| position + it)
+            }
+
+            // Neighbours sharing a face, an edge or a corner.
+            pub fn neighbours_26(&self) -> impl Iterator<Item=Self> {
+                let position = *self;
+                (-1..=1)
+                    .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| Self(x, y, z))))
+                    .filter(|it| *it != Self(0, 0, 0))
+                    .map(move |it| position + it)
+            }
+        }
+
+        impl Neg for Vec3<$type> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(-self.0, -self.1, -self.2)
+            }
+        }
+
+        impl BoundingBox<$type> {
+            // Same box, with a margin on every side.
+            pub fn expand(&self, margin: $type) -> Self {
+                let margin = Vec3(margin, margin, margin);
+                Self {
+                    min: self.min - margin,
+                    max: self.max + margin,
+                }
+            }
+
+            // All positions inside the box (inclusive), X first.
+            pub fn positions(&self) -> impl Iterator<Item=Vec3<$type>> {
+                let (min, max) = (self.min, self.max);
+                (min.2..=max.2).flat_map(move |z| {
+                    (min.1..=max.1).flat_map(move |y| (min.0..=max.0).map(move |x| Vec3(x, y, z)))
+                })
+            }
+        }
+    )*}
+}
+
+impl_vec3!(usize, isize, i64);
+impl_vec3_signed!(isize, i64);
+
+impl<T> TryFromLine for Vec3<T>
+    where T: TryFromLine {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let mut parts = line.split(',').map(|it| it.trim());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(x), Some(y), Some(z), None) => Ok(Self(T::try_from_line(x)?, T::try_from_line(y)?, T::try_from_line(z)?)),
+            _ => Err(ParseError::new(line, "vec3 should have three parts")),
+        }
     }
 }
 
 #[macro_export]
-macro_rules! vec2 {
-    ($x:expr, $y:expr) => {
-        Vec2::new($x, $y)
+macro_rules! vec3 {
+    ($x:expr, $y:expr, $z:expr) => {
+        Vec3::new($x, $y, $z)
     }
 }
 
-fn checked_add_signed(lhs: usize, rhs: isize) -> Option<usize> {
-    if rhs >= 0 {
-        lhs.checked_add(rhs as usize)
-    } else {
-        lhs.checked_sub(rhs.unsigned_abs())
+// Smallest box containing some positions. Both corners are inclusive.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct BoundingBox<T> {
+    min: Vec3<T>,
+    max: Vec3<T>,
+}
+
+impl<T> BoundingBox<T>
+    where T: Copy + Ord {
+    pub fn of(positions: impl IntoIterator<Item=Vec3<T>>) -> Option<Self> {
+        positions.into_iter().fold(None, |bounds: Option<Self>, position| {
+            Some(match bounds {
+                Some(bounds) => Self {
+                    min: Vec3(bounds.min.0.min(position.0), bounds.min.1.min(position.1), bounds.min.2.min(position.2)),
+                    max: Vec3(bounds.max.0.max(position.0), bounds.max.1.max(position.1), bounds.max.2.max(position.2)),
+                },
+                None => Self {
+                    min: position,
+                    max: position,
+                },
+            })
+        })
+    }
+
+    pub fn min(&self) -> Vec3<T> {
+        self.min
+    }
+
+    pub fn max(&self) -> Vec3<T> {
+        self.max
+    }
+
+    pub fn contains(&self, position: Vec3<T>) -> bool {
+        (self.min.0..=self.max.0).contains(&position.0)
+            && (self.min.1..=self.max.1).contains(&position.1)
+            && (self.min.2..=self.max.2).contains(&position.2)
     }
 }
 
-fn checked_add_unsigned(lhs: isize, rhs: usize) -> Option<isize> {
-    let rhs_div = (rhs / 2) as isize;
-    let rhs_rem = (rhs % 2) as isize;
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn vec2_arithmetic() {
+        let a: Vec2<isize> = vec2!(3, -4);
+        let b: Vec2<isize> = vec2!(-1, 2);
+
+        assert_eq!(a + b, vec2!(2, -2));
+        assert_eq!(a - b, vec2!(4, -6));
+        assert_eq!(-a, vec2!(-3, 4));
+        assert_eq!(a * 3, vec2!(9, -12));
+        assert_eq!(vec2!(2usize, 3) - vec2!(1, 1), vec2!(1, 2));
+    }
+
+    #[test]
+    fn vec2_signum() {
+        assert_eq!(vec2!(-5isize, 0).signum(), vec2!(-1, 0));
+        assert_eq!(vec2!(3i64, -7).signum(), vec2!(1, -1));
+        assert_eq!(vec2!(0isize, 0).signum(), vec2!(0, 0));
+    }
+
+    #[test]
+    fn vec2_distances() {
+        let a: Vec2<i64> = vec2!(1, -2);
+        let b: Vec2<i64> = vec2!(-3, 5);
+
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(a.chebyshev(b), 7);
+        assert_eq!(vec2!(1usize, 8).manhattan(vec2!(4, 2)), 9);
+        assert_eq!(vec2!(1usize, 8).chebyshev(vec2!(4, 2)), 6);
+    }
 
-    lhs.checked_add(rhs_div)
-        .and_then(|lhs| lhs.checked_add(rhs_div))
-        .and_then(|lhs| lhs.checked_add(rhs_rem))
+    // Y points down : right, down, left, up.
+    #[test]
+    fn vec2_rotations() {
+        let right: Vec2<isize> = vec2!(1, 0);
+        let down = right.rotate_clockwise();
+
+        assert_eq!(down, vec2!(0, 1));
+        assert_eq!(down.rotate_clockwise(), vec2!(-1, 0));
+        assert_eq!(down.rotate_clockwise().rotate_clockwise(), vec2!(0, -1));
+        assert_eq!(right.rotate_counterclockwise(), vec2!(0, -1));
+        assert_eq!(right.rotate_clockwise().rotate_counterclockwise(), right);
+    }
+
+    #[test]
+    fn vec2_conversions() {
+        assert_eq!(Vec2::<usize>::try_from(vec2!(2isize, 3)), Ok(vec2!(2, 3)));
+        assert!(Vec2::<usize>::try_from(vec2!(-1isize, 3)).is_err());
+        assert!(Vec2::<usize>::try_from(vec2!(2i64, -3)).is_err());
+        assert!(Vec2::<isize>::try_from(vec2!(usize::MAX, 0)).is_err());
+    }
+
+    #[test]
+    fn vec2_adding_a_direction() {
+        assert_eq!(vec2!(1usize, 1) + vec2!(-1isize, 1), Some(vec2!(0, 2)));
+        assert_eq!(vec2!(0usize, 1) + vec2!(-1isize, 0), None);
+    }
+
+    #[test]
+    fn vec2_parsing() {
+        assert_eq!(Vec2::<i64>::try_from_line("3,-4").ok(), Some(vec2!(3, -4)));
+        assert_eq!(Vec2::<i64>::try_from_line("x=-2, y=15").ok(), Some(vec2!(-2, 15)));
+        assert!(Vec2::<i64>::try_from_line("3").is_err());
+        assert!(Vec2::<i64>::try_from_line("x=1, y=a").is_err());
+    }
+
+    #[test]
+    fn vec3_neighbours_6() {
+        let position: Vec3<i64> = vec3!(1, 2, 3);
+        let neighbours: HashSet<_> = position.neighbours_6().collect();
+
+        assert_eq!(neighbours.len(), 6);
+        assert!(!neighbours.contains(&position));
+        assert!(neighbours.iter().all(|it| it.manhattan(position) == 1));
+    }
+
+    #[test]
+    fn vec3_neighbours_26() {
+        let position: Vec3<isize> = vec3!(-1, 0, 1);
+        let neighbours: HashSet<_> = position.neighbours_26().collect();
+
+        assert_eq!(neighbours.len(), 26);
+        assert!(!neighbours.contains(&position));
+        assert!(neighbours.iter().all(|it| it.chebyshev(position) == 1));
+    }
+
+    #[test]
+    fn vec3_parsing() {
+        assert_eq!(Vec3::<i64>::try_from_line("1,-2,3").ok(), Some(vec3!(1, -2, 3)));
+        assert_eq!(Vec3::<i64>::try_from_line(" 1, 2 , 3").ok(), Some(vec3!(1, 2, 3)));
+        assert!(Vec3::<i64>::try_from_line("1,2").is_err());
+        assert!(Vec3::<i64>::try_from_line("1,2,3,4").is_err());
+        assert!(Vec3::<i64>::try_from_line("1,b,3").is_err());
+    }
+
+    #[test]
+    fn bounding_box() {
+        let bounds = BoundingBox::of([vec3!(1, 5, -2), vec3!(3, 0, 4), vec3!(2, 2, 2)]);
+
+        assert_eq!(bounds.map(|it| (it.min(), it.max())), Some((vec3!(1, 0, -2), vec3!(3, 5, 4))));
+        assert_eq!(BoundingBox::<i64>::of([]), None);
+    }
+
+    #[test]
+    fn bounding_box_contains() {
+        let Some(bounds) = BoundingBox::<i64>::of([vec3!(0, 0, 0), vec3!(2, 2, 2)]) else { panic!("bounds should exist") };
+
+        assert!(bounds.contains(vec3!(0, 0, 0)));
+        assert!(bounds.contains(vec3!(2, 2, 2)));
+        assert!(bounds.contains(vec3!(1, 2, 0)));
+        assert!(!bounds.contains(vec3!(3, 1, 1)));
+        assert!(!bounds.contains(vec3!(1, -1, 1)));
+    }
+
+    #[test]
+    fn bounding_box_expand() {
+        let Some(bounds) = BoundingBox::<i64>::of([vec3!(0, 0, 0), vec3!(1, 1, 1)]) else { panic!("bounds should exist") };
+        let expanded = bounds.expand(1);
+
+        assert_eq!((expanded.min(), expanded.max()), (vec3!(-1, -1, -1), vec3!(2, 2, 2)));
+        assert_eq!(bounds.positions().count(), 8);
+        assert_eq!(expanded.positions().count(), 64);
+        assert!(expanded.positions().all(|it| expanded.contains(it)));
+    }
+
+    fn location(error: &ParseError) -> Option<(usize, usize)> {
+        error.location.as_ref().and_then(|it| it.line)
+    }
+
+    #[test]
+    fn locate_first_line() {
+        let file = "abc\ndef\n";
+        let error = ParseError::new(&file[1..2], "letter is not valid").locate(Path::new("input.txt"), file);
+
+        assert_eq!(location(&error), Some((1, 2)));
+        assert_eq!(error.to_string(), "input.txt:1:2 : letter is not valid (found \"b\")");
+    }
+
+    #[test]
+    fn locate_later_line() {
+        let file = "abc\ndéf\nghi";
+        let lines: Vec<_> = file.lines().collect();
+
+        // Columns are counted in chars, not bytes.
+        let error = ParseError::new(&lines[1][3..], "letter is not valid").locate(Path::new("input.txt"), file);
+        assert_eq!(location(&error), Some((2, 3)));
+        let error = ParseError::new(lines[2], "line is not valid").locate(Path::new("input.txt"), file);
+        assert_eq!(location(&error), Some((3, 1)));
+        // Errors from a single char are located by their slice.
+        let error = ParseError::for_char('h', "letter is not valid").at(&lines[2][1..2]).locate(Path::new("input.txt"), file);
+        assert_eq!(location(&error), Some((3, 2)));
+    }
+
+    #[test]
+    fn locate_foreign_text() {
+        let file = "abc\ndef";
+        let other = String::from("xyz");
+
+        let error = ParseError::new(&other[1..], "text is not valid").locate(Path::new("input.txt"), file);
+        assert_eq!(location(&error), None);
+        assert_eq!(error.to_string(), "input.txt : text is not valid (found \"yz\")");
+        // The address may fall inside the file, but the text does not match.
+        let address = file.as_ptr() as usize + 4;
+        let error = ParseError { address: Some(address), ..ParseError::new("xyz", "text is not valid") };
+        assert_eq!(location(&error.locate(Path::new("input.txt"), file)), None);
+        let error = ParseError::new("abc", "text is not valid").locate(Path::new("input.txt"), "");
+        assert_eq!(location(&error), None);
+        assert_eq!(location(&ParseError::missing("line is missing").locate(Path::new("input.txt"), file)), None);
+    }
 }
@@ -0,0 +1,101 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Allocations are only counted when the feature is enabled, since counting slows every
+// allocation down a bit :
+//
+//    cargo run --release --features count-allocations -- run 12
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// System allocator, which keeps count of what goes through it. Counters are shared by all threads.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn allocated(size: usize) {
+        COUNT.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn deallocated(size: usize) {
+        LIVE.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() { Self::allocated(layout.size()); }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() { Self::allocated(layout.size()); }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        Self::deallocated(layout.size());
+    }
+
+    // A reallocation counts as freeing the old block, and allocating the new one.
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            Self::deallocated(layout.size());
+            Self::allocated(new_size);
+        }
+        new_pointer
+    }
+}
+
+// What an operation allocated. Peak is the most bytes alive at once, on top of what was
+// alive before the operation.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+pub struct Allocations {
+    pub count: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+impl Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} bytes, peak {} bytes)", self.count, self.bytes, self.peak)
+    }
+}
+
+pub fn is_enabled() -> bool {
+    cfg!(feature = "count-allocations")
+}
+
+// Allocations of an operation, if they are counted. Operations should not overlap, since
+// counters are shared.
+pub fn count<T, F>(operation: F) -> (Option<Allocations>, T)
+    where F: FnOnce() -> T {
+    if !is_enabled() { return (None, operation()); }
+
+    let count = COUNT.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+
+    let value = operation();
+
+    let allocations = Allocations {
+        count: COUNT.load(Ordering::Relaxed) - count,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+    };
+    (Some(allocations), value)
+}
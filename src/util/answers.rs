@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use super::{ParseError, ParseResult, TryFromLines};
+
+// Known answers, by day. Uses the same format as the output of the runner :
+//
+//    Day 1
+//    Part 1 : 24000
+//    Part 2 : 45000
+//
+// Multiline answers (like drawings) start on the line after the part header. Timings and
+// checks are ignored, so the output of the runner can be used as is. Days which are not
+// implemented have no answers.
+#[derive(Debug, Default)]
+pub struct Answers(HashMap<usize, DayAnswers>);
+
+#[derive(Debug, Default, Clone)]
+pub struct DayAnswers {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl Answers {
+    pub fn day(&self, day: usize) -> Option<&DayAnswers> {
+        self.0.get(&day)
+    }
+
+    pub fn check(&self, day: usize, part_1: &str, part_2: &str) -> (Check, Check) {
+        let answers = self.day(day);
+        let part_1 = Check::new(answers.and_then(|it| it.part_1.as_deref()), part_1);
+        let part_2 = Check::new(answers.and_then(|it| it.part_2.as_deref()), part_2);
+        (part_1, part_2)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Check {
+    Pass,
+    Fail(String),
+    Missing,
+}
+
+impl Check {
+    fn new(expected: Option<&str>, actual: &str) -> Self {
+        match expected {
+            Some(expected) if expected == actual => Self::Pass,
+            Some(expected) => Self::Fail(expected.into()),
+            None => Self::Missing,
+        }
+    }
+
+    pub fn is_fail(&self) -> bool {
+        matches!(self, Self::Fail(_))
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Pass => write!(f, "pass"),
+            Check::Fail(expected) if expected.contains('\n') => write!(f, "fail (expected :\n{expected})"),
+            Check::Fail(expected) => write!(f, "fail (expected {expected})"),
+            Check::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+impl TryFromLines for Answers {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let mut answers = HashMap::new();
+
+        let mut day = None;
+        let mut lines = lines.iter().copied().peekable();
+        while let Some(line) = lines.next() {
+            if line.trim().is_empty() || is_ignored(line) { continue; }
+
+            if let Some(value) = line.strip_prefix("Day ") {
+                let value = value.trim().parse().map_err(|_| ParseError::new(value, "day should be a number"))?;
+                answers.entry(value).or_insert_with(DayAnswers::default);
+                day = Some(value);
+            } else if let Some((part, answer)) = line.split_once(':') {
+                let day = day.ok_or_else(|| ParseError::new(line, "answer should be preceded by a day"))?;
+                let day = answers.get_mut(&day).expect("day should exist since it was inserted");
+
+                let part = match part.trim() {
+                    "Part 1" => &mut day.part_1,
+                    "Part 2" => &mut day.part_2,
+                    _ => return Err(ParseError::new(part, "part should be \"Part 1\" or \"Part 2\"")),
+                };
+
+                let answer = answer.trim();
+                *part = Some(if answer.is_empty() {
+                    // Multiline answer. Stops at the first empty line or at the next header.
+                    let mut answer = Vec::new();
+                    while let Some(line) = lines.next_if(|it| !is_header(it)) {
+                        answer.push(line);
+                    }
+                    answer.join("\n")
+                } else {
+                    answer.into()
+                });
+            } else {
+                return Err(ParseError::new(line, "line should be a day or an answer"));
+            }
+        }
+
+        Ok(Self(answers))
+    }
+}
+
+fn is_header(line: &str) -> bool {
+    line.trim().is_empty() || line.starts_with("Day ") || line.starts_with("Part ") || is_ignored(line)
+}
+
+fn is_ignored(line: &str) -> bool {
+    line.starts_with("Time ") || line.starts_with("Total time ") || line.starts_with("Check ") || line.starts_with("Export ") || line.starts_with("Allocations ") || line == "Not implemented"
+}
@@ -0,0 +1,95 @@
+use std::fmt::{self, Display};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// Part of the budget spent warming up (caches, branch predictors, CPU frequency...).
+const WARM_UP_RATIO: u32 = 10;
+
+// Repeat an operation until the time budget is spent. Warms up first, and always takes at
+// least one sample, even if a single run is longer than the budget.
+pub fn measure<T, F>(budget: Duration, mut operation: F) -> Stats
+    where F: FnMut() -> T {
+    let warm_up = budget / WARM_UP_RATIO;
+    let start = Instant::now();
+    while start.elapsed() < warm_up {
+        black_box(operation());
+    }
+
+    let mut samples = Vec::new();
+    let start = Instant::now();
+    while samples.is_empty() || start.elapsed() < budget {
+        let sample_start = Instant::now();
+        black_box(operation());
+        samples.push(sample_start.elapsed());
+    }
+
+    Stats::new(samples)
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        if samples.is_empty() { panic!("stats should have at least one sample"); }
+        samples.sort();
+
+        let count = samples.len();
+        let min = samples[0];
+        let median = if count.is_multiple_of(2) {
+            (samples[count / 2 - 1] + samples[count / 2]) / 2
+        } else {
+            samples[count / 2]
+        };
+
+        let mean = samples.iter().map(|it| it.as_secs_f64()).sum::<f64>() / count as f64;
+        let variance = if count > 1 {
+            samples.iter().map(|it| (it.as_secs_f64() - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            samples: count,
+            min,
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Benchmark {
+    pub parse: Stats,
+    pub part_1: Stats,
+    pub part_2: Stats,
+    // Parsing a streamed input, if the solution supports it.
+    pub stream: Option<Stats>,
+}
+
+impl Display for Benchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<8}{:>10}{:>16}{:>16}{:>16}{:>16}", "", "Samples", "Min", "Median", "Mean", "Stddev")?;
+        let phases = [("Parse", Some(&self.parse)), ("Stream", self.stream.as_ref()), ("Part 1", Some(&self.part_1)), ("Part 2", Some(&self.part_2))];
+        for (name, stats) in phases.into_iter().filter_map(|(name, stats)| Some((name, stats?))) {
+            writeln!(
+                f,
+                "{:<8}{:>10}{:>13} ns{:>13} ns{:>13} ns{:>13} ns",
+                name,
+                stats.samples,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
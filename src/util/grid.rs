@@ -0,0 +1,274 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::vec2;
+
+use super::{chars_to, ParseError, ParseResult, TryFromChar, TryFromLines, Vec2};
+
+type Position = Vec2<usize>;
+type Direction = Vec2<isize>;
+
+// Top, right, bottom and left.
+const NEIGHBOURS_4: [Direction; 4] = [vec2!(0, -1), vec2!(1, 0), vec2!(0, 1), vec2!(-1, 0)];
+
+// Same as above, with diagonals. Clockwise, starting at the top.
+const NEIGHBOURS_8: [Direction; 8] = [
+    vec2!(0, -1), vec2!(1, -1), vec2!(1, 0), vec2!(1, 1),
+    vec2!(0, 1), vec2!(-1, 1), vec2!(-1, 0), vec2!(-1, -1),
+];
+
+// Two dimensional grid, stored row by row. Position (0, 0) is the top left corner.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+        where T: Clone {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_in_bounds(&self, position: Position) -> bool {
+        position.x() < self.width && position.y() < self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        if self.is_in_bounds(position) {
+            Some(&self.cells[self.index(position)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if self.is_in_bounds(position) {
+            let index = self.index(position);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&T> + '_ {
+        self.cells.iter()
+    }
+
+    // All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item=Position> + '_ {
+        (0..self.height).flat_map(|y| (0..self.width).map(move |x| vec2!(x, y)))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> + '_ {
+        // Chunks of size 0 are not allowed.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item=&T> + '_ {
+        (0..self.height).map(move |y| &self[vec2!(x, y)])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item=impl Iterator<Item=&T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    // Neighbours (top, right, bottom and left) inside the grid.
+    pub fn neighbours_4(&self, position: Position) -> impl Iterator<Item=Position> + '_ {
+        self.neighbours(position, &NEIGHBOURS_4)
+    }
+
+    // Neighbours, including diagonals, inside the grid.
+    pub fn neighbours_8(&self, position: Position) -> impl Iterator<Item=Position> + '_ {
+        self.neighbours(position, &NEIGHBOURS_8)
+    }
+
+    pub fn map<U, F>(&self, operation: F) -> Grid<U>
+        where F: FnMut(&T) -> U {
+        Grid {
+            cells: self.cells.iter().map(operation).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn neighbours<'a>(&'a self, position: Position, directions: &'static [Direction]) -> impl Iterator<Item=Position> + 'a {
+        directions
+            .iter()
+            .filter_map(move |direction| (position + *direction).filter(|it| self.is_in_bounds(*it)))
+    }
+
+    fn index(&self, position: Position) -> usize {
+        position.y() * self.width + position.x()
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        self.get(position).expect("position should be inside the grid")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        self.get_mut(position).expect("position should be inside the grid")
+    }
+}
+
+impl<T> TryFromLines for Grid<T>
+    where T: TryFromChar {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let width = lines.first().map(|it| it.chars().count()).unwrap_or(0);
+        let height = lines.len();
+
+        let mut cells = Vec::with_capacity(width * height);
+        for line in lines {
+            if line.chars().count() != width { return Err(ParseError::new(line, "grid rows should all have the same width")); }
+
+            for cell in chars_to(line) {
+                cells.push(cell?);
+            }
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+}
+
+impl<T> Display for Grid<T>
+    where T: Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 { writeln!(f)?; }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Eq, PartialEq, Copy, Clone)]
+    struct Digit(u8);
+
+    impl TryFromChar for Digit {
+        fn try_from_char(char: char) -> ParseResult<Self> {
+            char.to_digit(10).map(|it| Self(it as u8)).ok_or_else(|| ParseError::for_char(char, "digit is not valid"))
+        }
+    }
+
+    impl Display for Digit {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    // 3 columns, 2 rows.
+    fn grid() -> Grid<Digit> {
+        Grid::try_from_lines(&["123", "456"]).unwrap_or_else(|it| panic!("{it}"))
+    }
+
+    #[test]
+    fn get_out_of_bounds() {
+        let mut grid = grid();
+
+        assert_eq!(grid.get(vec2!(2, 1)), Some(&Digit(6)));
+        assert_eq!(grid.get(vec2!(3, 0)), None);
+        assert_eq!(grid.get(vec2!(0, 2)), None);
+        assert_eq!(grid.get_mut(vec2!(3, 1)), None);
+
+        *grid.get_mut(vec2!(1, 0)).unwrap_or_else(|| panic!("position should be inside the grid")) = Digit(0);
+        assert_eq!(grid[vec2!(1, 0)], Digit(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "position should be inside the grid")]
+    fn index_out_of_bounds() {
+        let _ = grid()[vec2!(3, 0)];
+    }
+
+    #[test]
+    fn neighbours_4() {
+        let grid = Grid::new(3, 3, 0);
+
+        let center: Vec<_> = grid.neighbours_4(vec2!(1, 1)).collect();
+        assert_eq!(center, vec![vec2!(1, 0), vec2!(2, 1), vec2!(1, 2), vec2!(0, 1)]);
+        let edge: Vec<_> = grid.neighbours_4(vec2!(1, 0)).collect();
+        assert_eq!(edge, vec![vec2!(2, 0), vec2!(1, 1), vec2!(0, 0)]);
+        let corner: Vec<_> = grid.neighbours_4(vec2!(2, 2)).collect();
+        assert_eq!(corner, vec![vec2!(2, 1), vec2!(1, 2)]);
+    }
+
+    #[test]
+    fn neighbours_8() {
+        let grid = Grid::new(3, 3, 0);
+
+        assert_eq!(grid.neighbours_8(vec2!(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours_8(vec2!(1, 0)).count(), 5);
+        let corner: Vec<_> = grid.neighbours_8(vec2!(0, 0)).collect();
+        assert_eq!(corner, vec![vec2!(1, 0), vec2!(1, 1), vec2!(0, 1)]);
+        assert_eq!(Grid::new(1, 1, 0).neighbours_8(vec2!(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+
+        assert_eq!(grid.row(1), &[Digit(4), Digit(5), Digit(6)]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![Digit(3), Digit(6)]);
+        let columns: Vec<Vec<_>> = grid.columns().map(|it| it.map(|it| it.0).collect()).collect();
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        let positions: Vec<_> = grid.positions().collect();
+        assert_eq!(positions, vec![vec2!(0, 0), vec2!(1, 0), vec2!(2, 0), vec2!(0, 1), vec2!(1, 1), vec2!(2, 1)]);
+    }
+
+    #[test]
+    fn empty() {
+        let grid = Grid::<Digit>::try_from_lines(&[]).unwrap_or_else(|it| panic!("{it}"));
+
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.rows().count(), 0);
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let ragged = Grid::<Digit>::try_from_lines(&["123", "45"]).err().map(|it| it.message().to_string());
+        assert_eq!(ragged.as_deref(), Some("grid rows should all have the same width"));
+        let invalid = Grid::<Digit>::try_from_lines(&["123", "4x6"]).err().map(|it| it.message().to_string());
+        assert_eq!(invalid.as_deref(), Some("digit is not valid"));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(grid().to_string(), "123\n456");
+        assert_eq!(grid().map(|it| it.0 % 2).to_string(), "101\n010");
+    }
+}
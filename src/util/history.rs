@@ -0,0 +1,322 @@
+use std::fmt::{self, Display};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::try_line_to;
+
+use super::{read, ParseError, ParseResult, Puzzle, ReadError, TryFromLine, TryFromLines};
+
+const HEADER: &str = "timestamp,commit,machine,command,day,input,parameters,phase,nanos";
+// Before inputs and parameters were recorded. Those entries are kept, but never a baseline.
+const OLD_HEADER: &str = "timestamp,commit,machine,command,day,phase,nanos";
+
+// Very short phases are mostly noise. Slowdowns smaller than this are never regressions.
+const MIN_SLOWDOWN: Duration = Duration::from_micros(1);
+
+// Used when the commit or the machine can't be found.
+const UNKNOWN: &str = "unknown";
+
+// Timing of a phase, for a day, at a commit, on a machine. Runs and benchmarks are kept apart,
+// since a single run is much noisier than the median of a benchmark. So are inputs and
+// parameters, since they change what is measured.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub timestamp: u64,
+    pub commit: String,
+    pub machine: String,
+    pub command: String,
+    pub day: usize,
+    // Path of the input file.
+    pub input: String,
+    // Overridden parameters, as "name=value" separated by semicolons.
+    pub parameters: String,
+    pub phase: String,
+    pub time: Duration,
+}
+
+// Timings of all the previous runs, in the order they were recorded. Stored as CSV :
+//
+//    timestamp,commit,machine,command,day,input,parameters,phase,nanos
+//    1700000000,1a2b3c4,laptop,bench,8,inputs/day8.txt,,part_1,123456
+//    1700000000,1a2b3c4,laptop,bench,15,inputs/day15.txt,part_1_height=10,part_1,123456
+#[derive(Debug, Default)]
+pub struct History(Vec<Entry>);
+
+impl History {
+    // Latest timing of the same phase, measured the same way on the same machine and puzzle, before
+    // this commit.
+    fn baseline(&self, entry: &Entry) -> Option<&Entry> {
+        self.0.iter().rev().find(|it| {
+            it.commit != entry.commit
+                && it.machine == entry.machine
+                && it.command == entry.command
+                && it.day == entry.day
+                && it.input == entry.input
+                && it.parameters == entry.parameters
+                && it.phase == entry.phase
+        })
+    }
+}
+
+// A phase which got slower than its baseline, by more than the threshold.
+#[derive(Debug, Clone)]
+pub struct Regression {
+    pub day: usize,
+    pub phase: String,
+    pub baseline: Entry,
+    pub time: Duration,
+}
+
+impl Regression {
+    pub fn ratio(&self) -> f64 {
+        self.time.as_secs_f64() / self.baseline.time.as_secs_f64()
+    }
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} {} is {:.0} % slower than at {} ({} ns -> {} ns)",
+            self.day,
+            self.phase,
+            (self.ratio() - 1.0) * 100.0,
+            self.baseline.commit,
+            self.baseline.time.as_nanos(),
+            self.time.as_nanos()
+        )
+    }
+}
+
+// Records the timings of a command, and compares them with the history. Timings are appended
+// to the file all at once, when saved.
+#[derive(Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    history: History,
+    threshold: f64,
+    timestamp: u64,
+    commit: String,
+    machine: String,
+    command: String,
+    entries: Vec<Entry>,
+}
+
+impl Recorder {
+    // Threshold is in percent. A missing file is an empty history.
+    pub fn open(path: impl Into<PathBuf>, command: &str, threshold: f64) -> Result<Self, ReadError> {
+        let path = path.into();
+        let history = if path.exists() { read(&path)? } else { History::default() };
+
+        Ok(Self {
+            path,
+            history,
+            threshold,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_secs()).unwrap_or(0),
+            commit: current_commit(),
+            machine: machine(),
+            command: command.into(),
+            entries: Vec::new(),
+        })
+    }
+
+    pub fn record(&mut self, day: usize, puzzle: &Puzzle, phase: &str, time: Duration) -> Option<Regression> {
+        let parameters: Vec<String> = puzzle.parameters.iter().map(|(name, value)| format!("{name}={value}")).collect();
+        let entry = Entry {
+            timestamp: self.timestamp,
+            commit: self.commit.clone(),
+            machine: self.machine.clone(),
+            command: self.command.clone(),
+            day,
+            input: field(&puzzle.source.path().to_string_lossy()),
+            parameters: field(&parameters.join(";")),
+            phase: phase.into(),
+            time,
+        };
+
+        let regression = self.history
+            .baseline(&entry)
+            .filter(|it| time.saturating_sub(it.time) >= MIN_SLOWDOWN)
+            .filter(|it| time.as_secs_f64() > it.time.as_secs_f64() * (1.0 + self.threshold / 100.0))
+            .map(|it| Regression {
+                day,
+                phase: phase.into(),
+                baseline: it.clone(),
+                time,
+            });
+
+        self.entries.push(entry);
+        regression
+    }
+
+    // Append the recorded timings. The header is written with the first ones.
+    pub fn save(self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent().filter(|it| !it.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let is_new = !self.path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut lines = String::new();
+        if is_new { lines.push_str(HEADER); lines.push('\n'); }
+        for entry in &self.entries {
+            lines.push_str(&entry.to_string());
+            lines.push('\n');
+        }
+        file.write_all(lines.as_bytes())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// Short hash of the checked out commit. Uncommitted changes are marked, since they are not
+// part of the commit.
+fn current_commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|it| it.status.success())
+            .and_then(|it| String::from_utf8(it.stdout).ok())
+    };
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) => {
+            let is_dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|it| !it.trim().is_empty());
+            format!("{}{}", commit.trim(), if is_dirty { "-dirty" } else { "" })
+        }
+        None => UNKNOWN.into(),
+    }
+}
+
+fn machine() -> String {
+    let hostname = fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| Command::new("hostname").output().ok().and_then(|it| String::from_utf8(it.stdout).ok()))
+        .map(|it| it.trim().to_string())
+        .filter(|it| !it.is_empty());
+
+    hostname.map(|it| field(&it)).unwrap_or_else(|| UNKNOWN.into())
+}
+
+// Commas would break the file.
+fn field(value: &str) -> String {
+    value.replace(',', "_")
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{},{},{},{},{},{}",
+            self.timestamp,
+            self.commit,
+            self.machine,
+            self.command,
+            self.day,
+            self.input,
+            self.parameters,
+            self.phase,
+            self.time.as_nanos()
+        )
+    }
+}
+
+impl TryFromLines for History {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let entries = lines
+            .iter()
+            .filter(|it| !it.trim().is_empty() && **it != HEADER && **it != OLD_HEADER)
+            .map(try_line_to!(Entry))
+            .collect::<ParseResult<_>>()?;
+
+        Ok(Self(entries))
+    }
+}
+
+impl TryFromLine for Entry {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let values: Vec<&str> = line.split(',').collect();
+        match values[..] {
+            [timestamp, commit, machine, command, day, input, parameters, phase, nanos] => Ok(Self {
+                timestamp: u64::try_from_line(timestamp)?,
+                commit: commit.into(),
+                machine: machine.into(),
+                command: command.into(),
+                day: usize::try_from_line(day)?,
+                input: input.into(),
+                parameters: parameters.into(),
+                phase: phase.into(),
+                time: Duration::from_nanos(u64::try_from_line(nanos)?),
+            }),
+            // The puzzle is unknown, so it matches no other entry.
+            [timestamp, commit, machine, command, day, phase, nanos] => Ok(Self {
+                timestamp: u64::try_from_line(timestamp)?,
+                commit: commit.into(),
+                machine: machine.into(),
+                command: command.into(),
+                day: usize::try_from_line(day)?,
+                input: UNKNOWN.into(),
+                parameters: UNKNOWN.into(),
+                phase: phase.into(),
+                time: Duration::from_nanos(u64::try_from_line(nanos)?),
+            }),
+            _ => Err(ParseError::new(line, format!("history entry should have {} values", HEADER.split(',').count()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(commit: &str, input: &str, parameters: &str) -> Entry {
+        Entry {
+            timestamp: 0,
+            commit: commit.into(),
+            machine: "laptop".into(),
+            command: "bench".into(),
+            day: 15,
+            input: input.into(),
+            parameters: parameters.into(),
+            phase: "part_1".into(),
+            time: Duration::from_nanos(100),
+        }
+    }
+
+    #[test]
+    fn baseline_has_the_same_input_and_parameters() {
+        let history = History(vec![
+            entry("a", "inputs/day15.txt", ""),
+            entry("b", "examples/day15.txt", ""),
+            entry("c", "inputs/day15.txt", "part_1_height=10"),
+        ]);
+
+        assert_eq!(history.baseline(&entry("d", "inputs/day15.txt", "")).map(|it| it.commit.as_str()), Some("a"));
+        assert_eq!(history.baseline(&entry("d", "examples/day15.txt", "")).map(|it| it.commit.as_str()), Some("b"));
+        assert_eq!(history.baseline(&entry("d", "inputs/day15.txt", "part_1_height=10")).map(|it| it.commit.as_str()), Some("c"));
+        assert!(history.baseline(&entry("d", "big.txt", "")).is_none());
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        let line = "1700000000,1a2b3c4,laptop,bench,15,inputs/day15.txt,part_1_height=10;part_2_height=20,part_1,123456";
+
+        assert_eq!(Entry::try_from_line(line).map(|it| it.to_string()).ok().as_deref(), Some(line));
+    }
+
+    #[test]
+    fn old_entries_are_never_a_baseline() {
+        let lines = [OLD_HEADER, "1700000000,1a2b3c4,laptop,bench,15,part_1,123456"];
+        let history = History::try_from_lines(&lines).unwrap_or_else(|it| panic!("{it}"));
+
+        assert_eq!(history.0.len(), 1);
+        assert!(history.baseline(&entry("d", "inputs/day15.txt", "")).is_none());
+    }
+}
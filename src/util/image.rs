@@ -0,0 +1,359 @@
+use std::fmt::{self, Display, Write as _};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use super::{Grid, Vec2};
+
+// Size of the biggest side of a SVG image, in pixels. Drawings use their own units.
+const SVG_SIZE: f64 = 1000.0;
+
+// Something which can be saved to a file.
+pub trait Image {
+    fn extension(&self) -> &'static str;
+    fn write(&self, output: &mut dyn Write) -> io::Result<()>;
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const GREEN: Rgb = Rgb(40, 180, 60);
+    pub const BLUE: Rgb = Rgb(40, 90, 220);
+    pub const YELLOW: Rgb = Rgb(230, 200, 60);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+
+    // Shade of gray.
+    pub fn gray(value: u8) -> Self {
+        Self(value, value, value)
+    }
+}
+
+// Hexadecimal notation, as used by SVG.
+impl Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// Grayscale image, saved as binary PGM.
+#[derive(Debug, Clone)]
+pub struct Graymap {
+    pixels: Grid<u8>,
+    scale: usize,
+}
+
+impl Graymap {
+    pub fn new(pixels: Grid<u8>) -> Self {
+        Self {
+            pixels,
+            scale: 1,
+        }
+    }
+
+    // Lowest value is black, highest value is white.
+    pub fn heatmap<T, F>(grid: &Grid<T>, value: F) -> Self
+        where F: Fn(&T) -> f64 {
+        let values = grid.map(value);
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { 1.0 };
+
+        Self::new(values.map(|it| ((it - min) / range * 255.0).round() as u8))
+    }
+
+    // Each cell is drawn as a square of this size. Puzzle grids are often small.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+}
+
+impl Image for Graymap {
+    fn extension(&self) -> &'static str {
+        "pgm"
+    }
+
+    fn write(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, "P5\n{} {}\n255\n", self.pixels.width() * self.scale, self.pixels.height() * self.scale)?;
+        write_scaled(output, &self.pixels, self.scale, |it| [*it])
+    }
+}
+
+// Color image, saved as binary PPM.
+#[derive(Debug, Clone)]
+pub struct Pixmap {
+    pixels: Grid<Rgb>,
+    scale: usize,
+}
+
+impl Pixmap {
+    pub fn new(pixels: Grid<Rgb>) -> Self {
+        Self {
+            pixels,
+            scale: 1,
+        }
+    }
+
+    // Gray levels become shades of gray. Useful to draw over a heatmap.
+    pub fn from_graymap(graymap: &Graymap) -> Self {
+        Self {
+            pixels: graymap.pixels.map(|it| Rgb::gray(*it)),
+            scale: graymap.scale,
+        }
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    pub fn pixel_mut(&mut self, position: Vec2<usize>) -> Option<&mut Rgb> {
+        self.pixels.get_mut(position)
+    }
+}
+
+impl Image for Pixmap {
+    fn extension(&self) -> &'static str {
+        "ppm"
+    }
+
+    fn write(&self, output: &mut dyn Write) -> io::Result<()> {
+        write!(output, "P6\n{} {}\n255\n", self.pixels.width() * self.scale, self.pixels.height() * self.scale)?;
+        write_scaled(output, &self.pixels, self.scale, |it| [it.0, it.1, it.2])
+    }
+}
+
+fn write_scaled<T, F, const N: usize>(output: &mut dyn Write, pixels: &Grid<T>, scale: usize, bytes: F) -> io::Result<()>
+    where F: Fn(&T) -> [u8; N] {
+    let mut row = Vec::with_capacity(pixels.width() * scale * N);
+    for y in 0..pixels.height() {
+        row.clear();
+        for pixel in pixels.row(y) {
+            let bytes = bytes(pixel);
+            for _ in 0..scale {
+                row.extend_from_slice(&bytes);
+            }
+        }
+        for _ in 0..scale {
+            output.write_all(&row)?;
+        }
+    }
+    Ok(())
+}
+
+// How a SVG shape is painted. Nothing by default.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Style {
+    fill: Option<Rgb>,
+    stroke: Option<(Rgb, f64)>,
+    opacity: Option<f64>,
+}
+
+impl Style {
+    pub fn fill(color: Rgb) -> Self {
+        Self { fill: Some(color), ..Self::default() }
+    }
+
+    pub fn stroke(color: Rgb, width: f64) -> Self {
+        Self { stroke: Some((color, width)), ..Self::default() }
+    }
+
+    pub fn opacity(mut self, opacity: f64) -> Self {
+        self.opacity = Some(opacity);
+        self
+    }
+}
+
+impl Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.fill {
+            Some(color) => write!(f, r#"fill="{color}""#)?,
+            None => write!(f, r#"fill="none""#)?,
+        }
+        if let Some((color, width)) = self.stroke {
+            write!(f, r#" stroke="{color}" stroke-width="{width}" stroke-linejoin="round" stroke-linecap="round""#)?;
+        }
+        if let Some(opacity) = self.opacity {
+            write!(f, r#" opacity="{opacity}""#)?;
+        }
+        Ok(())
+    }
+}
+
+// Vector image. Shapes are drawn in order, in the units of the drawing (y points down).
+#[derive(Debug, Clone)]
+pub struct Svg {
+    view_box: (f64, f64, f64, f64),
+    elements: String,
+}
+
+impl Svg {
+    // Part of the drawing which is visible.
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            view_box: (x, y, width, height),
+            elements: String::new(),
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, style: Style) {
+        let _ = writeln!(self.elements, r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" {style}/>"#);
+    }
+
+    pub fn circle(&mut self, x: f64, y: f64, radius: f64, style: Style) {
+        let _ = writeln!(self.elements, r#"<circle cx="{x}" cy="{y}" r="{radius}" {style}/>"#);
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), style: Style) {
+        let _ = writeln!(self.elements, r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {style}/>"#, from.0, from.1, to.0, to.1);
+    }
+
+    pub fn polyline(&mut self, points: &[(f64, f64)], style: Style) {
+        let _ = writeln!(self.elements, r#"<polyline points="{}" {style}/>"#, Self::points(points));
+    }
+
+    pub fn polygon(&mut self, points: &[(f64, f64)], style: Style) {
+        let _ = writeln!(self.elements, r#"<polygon points="{}" {style}/>"#, Self::points(points));
+    }
+
+    fn points(points: &[(f64, f64)]) -> String {
+        points.iter().map(|(x, y)| format!("{x},{y}")).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Image for Svg {
+    fn extension(&self) -> &'static str {
+        "svg"
+    }
+
+    fn write(&self, output: &mut dyn Write) -> io::Result<()> {
+        // Keep the aspect ratio of the drawing. An empty drawing has no size.
+        let (x, y, width, height) = self.view_box;
+        let largest = width.max(height);
+        let scale = if largest > 0.0 { SVG_SIZE / largest } else { 0.0 };
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{x} {y} {width} {height}" width="{}" height="{}">"#,
+            (width * scale).round(),
+            (height * scale).round()
+        )?;
+        output.write_all(self.elements.as_bytes())?;
+        writeln!(output, "</svg>")
+    }
+}
+
+// Saves the images of a puzzle inside a directory. Names are prefixed, so many puzzles can share it.
+#[derive(Debug)]
+pub struct Exporter {
+    directory: PathBuf,
+    prefix: String,
+    files: Vec<PathBuf>,
+}
+
+impl Exporter {
+    pub fn new(directory: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            files: Vec::new(),
+        }
+    }
+
+    pub fn save(&mut self, name: &str, image: &dyn Image) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        let path = self.directory.join(format!("{}_{name}.{}", self.prefix, image.extension()));
+        let mut output = BufWriter::new(File::create(&path)?);
+        image.write(&mut output)?;
+        output.flush()?;
+
+        self.files.push(path);
+        Ok(())
+    }
+
+    // Files saved so far.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vec2;
+
+    use super::*;
+
+    fn write(image: &dyn Image) -> Vec<u8> {
+        let mut output = Vec::new();
+        image.write(&mut output).unwrap_or_else(|it| panic!("{it}"));
+        output
+    }
+
+    fn grid<T: Copy>(width: usize, cells: &[T]) -> Grid<T> {
+        let mut grid = Grid::new(width, cells.len() / width, cells[0]);
+        for (position, cell) in grid.positions().collect::<Vec<_>>().into_iter().zip(cells) {
+            grid[position] = *cell;
+        }
+        grid
+    }
+
+    #[test]
+    fn graymap() {
+        let image = Graymap::new(grid(2, &[0, 1, 2, 3]));
+
+        assert_eq!(image.extension(), "pgm");
+        assert_eq!(write(&image), b"P5\n2 2\n255\n\x00\x01\x02\x03");
+        assert_eq!(write(&image.scale(2)), b"P5\n4 4\n255\n\x00\x00\x01\x01\x00\x00\x01\x01\x02\x02\x03\x03\x02\x02\x03\x03");
+    }
+
+    #[test]
+    fn heatmap() {
+        let image = Graymap::heatmap(&grid(3, &[-1.0, 0.0, 1.0]), |it| *it);
+        assert_eq!(write(&image), b"P5\n3 1\n255\n\x00\x80\xff");
+        // A flat grid is black, rather than a division by zero.
+        let image = Graymap::heatmap(&grid(2, &[5, 5]), |it| *it as f64);
+        assert_eq!(write(&image), b"P5\n2 1\n255\n\x00\x00");
+    }
+
+    #[test]
+    fn pixmap() {
+        let mut image = Pixmap::new(grid(2, &[Rgb::BLACK, Rgb::WHITE]));
+        if let Some(pixel) = image.pixel_mut(vec2!(0, 0)) { *pixel = Rgb(1, 2, 3); }
+
+        assert_eq!(image.extension(), "ppm");
+        assert!(image.pixel_mut(vec2!(2, 0)).is_none());
+        assert_eq!(write(&image), b"P6\n2 1\n255\n\x01\x02\x03\xff\xff\xff");
+        assert_eq!(write(&image.scale(0)), b"P6\n2 1\n255\n\x01\x02\x03\xff\xff\xff");
+        let image = Pixmap::from_graymap(&Graymap::new(grid(1, &[7])).scale(2));
+        assert_eq!(write(&image), b"P6\n2 2\n255\n\x07\x07\x07\x07\x07\x07\x07\x07\x07\x07\x07\x07");
+    }
+
+    #[test]
+    fn svg() {
+        let mut image = Svg::new(-1.0, 0.0, 4.0, 2.0);
+        image.rect(0.0, 0.0, 1.0, 2.0, Style::fill(Rgb::RED));
+        image.polyline(&[(0.0, 0.0), (1.5, -2.0)], Style::stroke(Rgb(0, 15, 255), 0.5).opacity(0.25));
+        image.circle(1.0, 1.0, 0.5, Style::default());
+
+        assert_eq!(image.extension(), "svg");
+        assert_eq!(String::from_utf8(write(&image)).ok().as_deref(), Some(concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-1 0 4 2" width="1000" height="500">"#, "\n",
+            r##"<rect x="0" y="0" width="1" height="2" fill="#dc2828"/>"##, "\n",
+            r##"<polyline points="0,0 1.5,-2" fill="none" stroke="#000fff" stroke-width="0.5" stroke-linejoin="round" stroke-linecap="round" opacity="0.25"/>"##, "\n",
+            r#"<circle cx="1" cy="1" r="0.5" fill="none"/>"#, "\n",
+            "</svg>\n",
+        )));
+    }
+
+    // An empty drawing is not scaled up to infinity.
+    #[test]
+    fn empty_svg() {
+        let image = String::from_utf8(write(&Svg::new(0.0, 0.0, 0.0, 0.0))).unwrap_or_default();
+
+        assert_eq!(image, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 0 0\" width=\"0\" height=\"0\">\n</svg>\n");
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, Sub};
+
+use super::{ParseError, ParseResult, TryFromLine};
+
+// Integer types usable as interval bounds.
+pub trait Integer: Copy + Ord + Add<Output=Self> + Sub<Output=Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    // Same value, in a type which holds the values of all bound types.
+    fn wide(self) -> i128;
+}
+
+macro_rules! impl_integer {
+    ($($type:ty),*) => {$(
+        impl Integer for $type {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn wide(self) -> i128 {
+                self as i128
+            }
+        }
+    )*}
+}
+
+impl_integer!(i32, i64, isize, u32, u64, usize);
+
+// Interval of integers. Both bounds are inclusive.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T> Interval<T>
+    where T: Integer {
+    pub fn new(start: T, end: T) -> Self {
+        if start > end { panic!("interval start should not be after its end"); }
+
+        Self {
+            start,
+            end,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    // Count of values inside the interval. Counted in a wider type, since a full range has one
+    // value more than the biggest one (ex. 0..=u32::MAX). Intervals are never empty.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u128 {
+        (self.end.wide() - self.start.wide()) as u128 + 1
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Self::new(self.start.max(other.start), self.end.min(other.end)))
+        } else {
+            None
+        }
+    }
+}
+
+// Parse "start-end". A leading minus sign belongs to the start.
+impl<T> TryFromLine for Interval<T>
+    where T: Integer + TryFromLine {
+    fn try_from_line(line: &str) -> ParseResult<Self> {
+        let separator = line.char_indices().skip(1).find(|(_, it)| *it == '-').map(|(index, _)| index);
+        let separator = separator.ok_or_else(|| ParseError::new(line, "interval should have a start and a end value"))?;
+        let start = T::try_from_line(&line[..separator])?;
+        let end = T::try_from_line(&line[separator + 1..])?;
+        if start > end { return Err(ParseError::new(line, "interval start should not be after its end")); }
+
+        Ok(Self::new(start, end))
+    }
+}
+
+impl<T> Display for Interval<T>
+    where T: Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.start, self.end)
+    }
+}
+
+// Set of integers, stored as sorted intervals. Overlapping or adjacent intervals are merged,
+// so there is always a gap of at least one value between two intervals.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> IntervalSet<T>
+    where T: Integer {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new()
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // Count of values inside the set. Same as the intervals, it may not fit in T.
+    pub fn length(&self) -> u128 {
+        self.intervals.iter().map(|it| it.len()).sum()
+    }
+
+    // Smallest interval containing the whole set.
+    pub fn bounds(&self) -> Option<Interval<T>> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => Some(Interval::new(first.start, last.end)),
+            _ => None
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|it| it.end < value);
+        self.intervals.get(index).is_some_and(|it| it.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // Intervals before the inserted one, that don't touch it, are kept as is.
+        let first = self.intervals.partition_point(|it| !touches(it.end, interval.start));
+        // Intervals from first to last touch the inserted one, and are merged into it.
+        let last = self.intervals.partition_point(|it| touches(interval.end, it.start));
+
+        let mut merged = interval;
+        if first < last {
+            merged.start = merged.start.min(self.intervals[first].start);
+            merged.end = merged.end.max(self.intervals[last - 1].end);
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for interval in &other.intervals {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        // Both are sorted. Walk them side by side, always moving the one ending first.
+        let (mut i, mut j) = (0, 0);
+        while let (Some(lhs), Some(rhs)) = (self.intervals.get(i), other.intervals.get(j)) {
+            intervals.extend(lhs.intersection(rhs));
+            if lhs.end < rhs.end { i += 1; } else { j += 1; }
+        }
+
+        Self {
+            intervals
+        }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        match self.bounds() {
+            Some(bounds) => self.intersection(&other.complement(bounds)),
+            None => Self::new(),
+        }
+    }
+
+    // Values inside the bounds that are not in the set.
+    pub fn complement(&self, bounds: Interval<T>) -> Self {
+        let mut intervals = Vec::new();
+
+        let mut start = Some(bounds.start);
+        for interval in self.intervals.iter().filter(|it| it.overlaps(&bounds)) {
+            let Some(current) = start else { break; };
+            if current < interval.start {
+                intervals.push(Interval::new(current, interval.start - T::ONE));
+            }
+            // Nothing left after the bounds (or after the biggest possible value).
+            start = Some(interval.end).filter(|it| *it < bounds.end).map(|it| it + T::ONE);
+        }
+        if let Some(start) = start {
+            intervals.push(Interval::new(start, bounds.end));
+        }
+
+        Self {
+            intervals
+        }
+    }
+
+    // Intervals between the intervals of the set.
+    pub fn gaps(&self) -> impl Iterator<Item=Interval<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|it| Interval::new(it[0].end + T::ONE, it[1].start - T::ONE))
+    }
+}
+
+impl<T> Default for IntervalSet<T>
+    where T: Integer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<Interval<T>> for IntervalSet<T>
+    where T: Integer {
+    fn from_iter<I: IntoIterator<Item=Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+// Whether an interval ending at "end" overlaps or is adjacent to an interval starting at "start".
+fn touches<T>(end: T, start: T) -> bool
+    where T: Integer {
+    start <= end || start - end == T::ONE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set<T>(intervals: &[(T, T)]) -> IntervalSet<T>
+        where T: Integer {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    #[test]
+    fn insert_merges_adjacent_intervals() {
+        assert_eq!(set(&[(1, 2), (3, 4)]).intervals(), &[Interval::new(1, 4)]);
+        assert_eq!(set(&[(3, 4), (1, 2)]).intervals(), &[Interval::new(1, 4)]);
+        assert_eq!(set(&[(1, 2), (4, 5)]).intervals(), &[Interval::new(1, 2), Interval::new(4, 5)]);
+    }
+
+    #[test]
+    fn insert_merges_every_touched_interval() {
+        let mut intervals = set(&[(1, 2), (5, 6), (9, 10), (14, 15)]);
+        intervals.insert(Interval::new(3, 11));
+
+        assert_eq!(intervals.intervals(), &[Interval::new(1, 11), Interval::new(14, 15)]);
+        assert_eq!(intervals.length(), 13);
+    }
+
+    #[test]
+    fn len_of_a_full_range() {
+        assert_eq!(Interval::new(3, 3).len(), 1);
+        assert_eq!(Interval::new(-2, 2).len(), 5);
+        assert_eq!(Interval::new(0, u32::MAX).len(), 1 << 32);
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).len(), 1 << 64);
+        assert_eq!(Interval::new(0, u64::MAX).len(), 1 << 64);
+    }
+
+    #[test]
+    fn length_of_a_full_range() {
+        assert_eq!(IntervalSet::<i32>::new().length(), 0);
+        assert_eq!(set(&[(0, 10), (u32::MAX - 9, u32::MAX)]).length(), 21);
+        assert_eq!(set(&[(0, 10), (11, u32::MAX)]).length(), 1 << 32);
+        assert_eq!(set(&[(i64::MIN, -1), (1, i64::MAX)]).length(), (1 << 64) - 1);
+    }
+
+    #[test]
+    fn union() {
+        let union = set(&[(1, 3), (10, 12)]).union(&set(&[(4, 5), (8, 8)]));
+
+        assert_eq!(union, set(&[(1, 5), (8, 8), (10, 12)]));
+    }
+
+    #[test]
+    fn complement() {
+        let intervals = set(&[(2, 3), (6, 7)]);
+
+        assert_eq!(intervals.complement(Interval::new(0, 10)), set(&[(0, 1), (4, 5), (8, 10)]));
+        assert_eq!(intervals.complement(Interval::new(3, 6)), set(&[(4, 5)]));
+        assert_eq!(IntervalSet::new().complement(Interval::new(0, 10)), set(&[(0, 10)]));
+    }
+
+    #[test]
+    fn complement_ending_at_the_bounds() {
+        let intervals = set(&[(2, 3), (6, 10)]);
+
+        assert_eq!(intervals.complement(Interval::new(0, 10)), set(&[(0, 1), (4, 5)]));
+        assert_eq!(intervals.complement(Interval::new(0, 8)), set(&[(0, 1), (4, 5)]));
+    }
+
+    #[test]
+    fn complement_ending_at_the_biggest_value() {
+        let intervals = set(&[(0, 10), (20, u32::MAX)]);
+
+        assert_eq!(intervals.complement(Interval::new(0, u32::MAX)), set(&[(11, 19)]));
+        assert_eq!(set(&[(0, u32::MAX)]).complement(Interval::new(0, u32::MAX)), IntervalSet::new());
+    }
+
+    #[test]
+    fn difference() {
+        let intervals = set(&[(0, 10), (20, 30)]);
+
+        assert_eq!(intervals.difference(&set(&[(5, 25)])), set(&[(0, 4), (26, 30)]));
+        assert_eq!(intervals.difference(&set(&[(-5, 40)])), IntervalSet::new());
+    }
+
+    #[test]
+    fn difference_with_an_empty_set() {
+        let intervals = set(&[(0, 10), (20, 30)]);
+
+        assert_eq!(intervals.difference(&IntervalSet::new()), intervals);
+        assert_eq!(IntervalSet::new().difference(&intervals), IntervalSet::new());
+    }
+
+    #[test]
+    fn gaps() {
+        let gaps: Vec<_> = set(&[(0, 2), (5, 5), (7, 9)]).gaps().collect();
+
+        assert_eq!(gaps, vec![Interval::new(3, 4), Interval::new(6, 6)]);
+        assert_eq!(set(&[(0, 2)]).gaps().count(), 0);
+        assert_eq!(IntervalSet::<i32>::new().gaps().count(), 0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use super::{ParseError, ParseResult, TryFromLines};
+
+// Named constants of a puzzle, which can be overridden by name. Declare them with parameters!.
+pub trait Parameters {
+    fn set(&mut self, name: &str, value: &str) -> ParseResult<()>;
+}
+
+// Declare the parameters of a puzzle, with their types and defaults. Values outside of the
+// optional range are rejected, rather than breaking the solution :
+//
+//    parameters! {
+//        pub struct Parameters {
+//            #[valid(1..)]
+//            rounds: usize = 20,
+//        }
+//    }
+#[macro_export]
+macro_rules! parameters {
+    ($vis:vis struct $name:ident { $($(#[valid($range:expr)])? $field:ident : $type:ty = $default:expr),* $(,)? }) => {
+        #[derive(Debug, Clone)]
+        $vis struct $name {
+            $($field: $type),*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: $default),*
+                }
+            }
+        }
+
+        impl $crate::util::parameters::Parameters for $name {
+            fn set(&mut self, name: &str, value: &str) -> $crate::util::ParseResult<()> {
+                match name {
+                    $(stringify!($field) => {
+                        let parsed = <$type as $crate::util::TryFromLine>::try_from_line(value).map_err(|it| {
+                            $crate::util::ParseError::new(value, format!("parameter \"{name}\" is not valid ({})", it.message()))
+                        })?;
+                        $(if !($range).contains(&parsed) {
+                            return Err($crate::util::ParseError::new(value, format!("parameter \"{name}\" should be in {:?}", $range)));
+                        })?
+                        self.$field = parsed;
+                    })*
+                    _ => return Err($crate::util::ParseError::new(name, "parameter does not exist")),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Parameter overrides, by day. Uses the same layout as the answers :
+//
+//    Day 15
+//    part_1_height = 10
+//    part_2_height = 20
+#[derive(Debug, Default)]
+pub struct Manifest(HashMap<usize, Vec<(String, String)>>);
+
+impl Manifest {
+    pub fn day(&self, day: usize) -> &[(String, String)] {
+        self.0.get(&day).map(|it| it.as_slice()).unwrap_or_default()
+    }
+}
+
+impl TryFromLines for Manifest {
+    fn try_from_lines(lines: &[&str]) -> ParseResult<Self> {
+        let mut manifest = HashMap::new();
+
+        let mut day = None;
+        for line in lines {
+            if line.trim().is_empty() { continue; }
+
+            if let Some(value) = line.strip_prefix("Day ") {
+                let value = value.trim().parse().map_err(|_| ParseError::new(value, "day should be a number"))?;
+                manifest.entry(value).or_insert_with(Vec::new);
+                day = Some(value);
+            } else if let Some((name, value)) = line.split_once('=') {
+                let day = day.ok_or_else(|| ParseError::new(line, "parameter should be preceded by a day"))?;
+                let parameters = manifest.get_mut(&day).expect("day should exist since it was inserted");
+                parameters.push((name.trim().into(), value.trim().into()));
+            } else {
+                return Err(ParseError::new(line, "line should be a day or a parameter"));
+            }
+        }
+
+        Ok(Self(manifest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    parameters! {
+        struct Example {
+            #[valid(1..)]
+            rounds: usize = 20,
+            #[valid(-5..=5)]
+            offset: i64 = 0,
+            name: String = "default".into(),
+        }
+    }
+
+    fn message(result: ParseResult<()>) -> Option<String> {
+        result.err().map(|it| it.message().to_string())
+    }
+
+    #[test]
+    fn set() {
+        let mut parameters = Example::default();
+
+        assert!(parameters.set("rounds", "1").is_ok());
+        assert!(parameters.set("offset", "-5").is_ok());
+        assert!(parameters.set("name", "other").is_ok());
+        assert_eq!((parameters.rounds, parameters.offset, parameters.name.as_str()), (1, -5, "other"));
+    }
+
+    #[test]
+    fn set_out_of_range() {
+        let mut parameters = Example::default();
+
+        assert_eq!(message(parameters.set("rounds", "0")).as_deref(), Some("parameter \"rounds\" should be in 1.."));
+        assert_eq!(message(parameters.set("offset", "6")).as_deref(), Some("parameter \"offset\" should be in -5..=5"));
+        // Rejected values are not kept.
+        assert_eq!((parameters.rounds, parameters.offset), (20, 0));
+    }
+
+    #[test]
+    fn set_invalid() {
+        let mut parameters = Example::default();
+
+        let error = message(parameters.set("rounds", "many"));
+        assert!(error.is_some_and(|it| it.starts_with("parameter \"rounds\" is not valid")));
+        assert_eq!(message(parameters.set("unknown", "1")).as_deref(), Some("parameter does not exist"));
+        assert_eq!(parameters.rounds, 20);
+    }
+
+    #[test]
+    fn manifest() {
+        let lines = ["Day 10", "crt_width = 5", "", "Day 15", "part_1_height=10", " part_2_height = 20 ", "Day 3"];
+        let manifest = Manifest::try_from_lines(&lines).unwrap_or_else(|it| panic!("{it}"));
+
+        assert_eq!(manifest.day(10), &[("crt_width".to_string(), "5".to_string())]);
+        assert_eq!(manifest.day(15), &[
+            ("part_1_height".to_string(), "10".to_string()),
+            ("part_2_height".to_string(), "20".to_string()),
+        ]);
+        assert!(manifest.day(3).is_empty());
+        assert!(manifest.day(4).is_empty());
+    }
+
+    #[test]
+    fn manifest_errors() {
+        let error = |lines: &[&str]| Manifest::try_from_lines(lines).err().map(|it| it.message().to_string());
+
+        assert_eq!(error(&["rounds = 1"]).as_deref(), Some("parameter should be preceded by a day"));
+        assert_eq!(error(&["Day ten"]).as_deref(), Some("day should be a number"));
+        assert_eq!(error(&["Day 1", "rounds"]).as_deref(), Some("line should be a day or a parameter"));
+    }
+}
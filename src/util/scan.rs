@@ -0,0 +1,155 @@
+use super::{ParseError, ParseResult};
+
+const PLACEHOLDER: &str = "{}";
+
+// Match a line against a pattern, where each "{}" captures some text. Whitespace is loose : any
+// amount of it (but at least some) matches any whitespace of the pattern, and captures are
+// trimmed, as is the start of the line. A capture ends where the text after it starts, or at
+// the next whitespace if there is none.
+// Captures are slices of the line, so errors stay located. See the parse! macro.
+pub fn scan<'a>(line: &'a str, pattern: &str, count: usize) -> ParseResult<Vec<&'a str>> {
+    let mut literals = pattern.split(PLACEHOLDER);
+    if literals.clone().count() != count + 1 { panic!("pattern \"{pattern}\" should have {count} placeholders"); }
+
+    let error = || ParseError::new(line, format!("line should match \"{pattern}\""));
+
+    let first = literals.next().expect("pattern should have at least one literal");
+    let mut rest = match_literal(line.trim_start(), first).ok_or_else(error)?;
+
+    let mut captures = Vec::with_capacity(count);
+    let mut literals = literals.peekable();
+    while let Some(literal) = literals.next() {
+        let is_last = literals.peek().is_none();
+
+        let (capture, after) = if literal.trim().is_empty() && is_last {
+            // Last capture takes the end of the line.
+            (rest.trim(), "")
+        } else if literal.trim().is_empty() {
+            // Captures only separated by whitespace. Stop at the next whitespace.
+            let text = rest.trim_start();
+            let end = text.find(char::is_whitespace).unwrap_or(text.len());
+            (&text[..end], match_literal(&text[end..], literal).ok_or_else(error)?)
+        } else {
+            find_literal(rest, literal).ok_or_else(error)?
+        };
+        if capture.is_empty() { return Err(error()); }
+
+        captures.push(capture);
+        rest = after;
+    }
+
+    if !rest.trim().is_empty() { return Err(error()); }
+    Ok(captures)
+}
+
+// Match a literal at the start of the text. Returns the text after it.
+fn match_literal<'a>(text: &'a str, literal: &str) -> Option<&'a str> {
+    let mut text = text;
+    let mut literal = literal;
+    while !literal.is_empty() {
+        let word = literal.trim_start();
+        if word.len() < literal.len() {
+            // Whitespace of the pattern needs some whitespace in the text.
+            let trimmed = text.trim_start();
+            if trimmed.len() == text.len() { return None; }
+            text = trimmed;
+        }
+
+        let end = word.find(char::is_whitespace).unwrap_or(word.len());
+        text = text.strip_prefix(&word[..end])?;
+        literal = &word[end..];
+    }
+    Some(text)
+}
+
+// Find the first occurrence of a literal after a non-empty capture. Returns the trimmed capture
+// and the text after the literal.
+fn find_literal<'a>(text: &'a str, literal: &str) -> Option<(&'a str, &'a str)> {
+    let first_word = literal.split_whitespace().next()?;
+    // Whitespace before the literal is between the capture and the literal.
+    let is_spaced = literal.starts_with(char::is_whitespace);
+    text.match_indices(first_word)
+        .map(|(index, _)| (&text[..index], &text[index..]))
+        .filter(|(capture, _)| !capture.trim().is_empty() && (!is_spaced || capture.ends_with(char::is_whitespace)))
+        .find_map(|(capture, after)| Some((capture.trim(), match_literal(after, literal.trim_start())?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn captures<'a>(line: &'a str, pattern: &str) -> Option<Vec<&'a str>> {
+        scan(line, pattern, pattern.matches(PLACEHOLDER).count()).ok()
+    }
+
+    #[test]
+    fn captures_in_order() {
+        assert_eq!(captures("move 1 from 2 to 3", "move {} from {} to {}"), Some(vec!["1", "2", "3"]));
+        assert_eq!(captures("Sensor at x=-2, y=15: closest", "Sensor at x={}, y={}: closest"), Some(vec!["-2", "15"]));
+    }
+
+    #[test]
+    fn whitespace_is_loose() {
+        assert_eq!(captures("move  1   from\t2 to 3", "move {} from {} to {}"), Some(vec!["1", "2", "3"]));
+        assert_eq!(captures("  Test: divisible by 23", "Test: divisible by {}"), Some(vec!["23"]));
+        assert_eq!(captures("move 1 from 2 to 3  ", "move {} from {} to {}"), Some(vec!["1", "2", "3"]));
+    }
+
+    #[test]
+    fn whitespace_is_required() {
+        assert_eq!(captures("Sensorat x=1, y=2", "Sensor at x={}, y={}"), None);
+        assert_eq!(captures("move 1from 2 to 3", "move {} from {} to {}"), None);
+        assert_eq!(captures("move 1 from2 to 3", "move {} from {} to {}"), None);
+        assert_eq!(captures("move1 from 2 to 3", "move {} from {} to {}"), None);
+    }
+
+    #[test]
+    fn captures_are_trimmed() {
+        assert_eq!(captures("a =  1 , b = 2", "a = {}, b = {}"), Some(vec!["1", "2"]));
+        assert_eq!(captures("items:   79, 98  ", "items: {}"), Some(vec!["79, 98"]));
+    }
+
+    #[test]
+    fn capture_at_the_end_of_the_line() {
+        assert_eq!(captures("Operation: new = old * 19", "Operation: new = old {}"), Some(vec!["* 19"]));
+        assert_eq!(captures("Operation: new = old", "Operation: new = old {}"), None);
+        // The last capture takes everything left, even with whitespace.
+        assert_eq!(captures("move 1 from 2 to 3 now", "move {} from {} to {}"), Some(vec!["1", "2", "3 now"]));
+    }
+
+    #[test]
+    fn capture_ends_at_the_first_occurrence_of_the_literal() {
+        assert_eq!(captures("a-b-c", "{}-{}"), Some(vec!["a", "b-c"]));
+        assert_eq!(captures("x to y to z", "{} to {}"), Some(vec!["x", "y to z"]));
+    }
+
+    #[test]
+    fn adjacent_placeholders() {
+        assert_eq!(captures("noop 12 ab", "{} {} {}"), Some(vec!["noop", "12", "ab"]));
+        assert_eq!(captures("addx   -3", "{} {}"), Some(vec!["addx", "-3"]));
+        assert_eq!(captures("addx", "{} {}"), None);
+        assert_eq!(captures("addx -3 4", "{} {}"), Some(vec!["addx", "-3 4"]));
+    }
+
+    #[test]
+    fn mismatches() {
+        assert_eq!(captures("move 1 from 2", "move {} from {} to {}"), None);
+        assert_eq!(captures("move  from 2 to 3", "move {} from {} to {}"), None);
+        assert_eq!(captures("move 1 from 2 to 3 now", "move {} from {} to 3"), None);
+        assert_eq!(captures("jump 1 from 2 to 3", "move {} from {} to {}"), None);
+    }
+
+    #[test]
+    fn errors_are_located_on_the_line() {
+        let line = "move 1 from 2";
+        let error = scan(line, "move {} from {} to {}", 3).expect_err("line should not match");
+
+        assert_eq!(error.text(), line);
+    }
+
+    #[test]
+    #[should_panic]
+    fn placeholder_count_should_match() {
+        let _ = scan("move 1", "move {}", 2);
+    }
+}
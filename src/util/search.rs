@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// Cost of an edge. Default is the cost of no edge at all.
+pub trait Cost: Copy + Ord + Add<Output=Self> + Default {}
+
+impl<T> Cost for T
+    where T: Copy + Ord + Add<Output=Self> + Default {}
+
+// Result of a search : cost of the best path from the closest start to every reached node.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    costs: HashMap<N, C>,
+    previous: HashMap<N, N>,
+}
+
+// Path from a start to a node. Starts with the start, ends with the node.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N, C> Search<N, C>
+    where N: Eq + Hash + Clone,
+          C: Cost {
+    fn new() -> Self {
+        Self {
+            costs: HashMap::new(),
+            previous: HashMap::new(),
+        }
+    }
+
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn is_reached(&self, node: &N) -> bool {
+        self.costs.contains_key(node)
+    }
+
+    // All reached nodes, in no particular order.
+    pub fn reached(&self) -> impl Iterator<Item=(&N, C)> + '_ {
+        self.costs.iter().map(|(node, cost)| (node, *cost))
+    }
+
+    pub fn path(&self, node: &N) -> Option<Path<N, C>> {
+        let cost = self.cost(node)?;
+
+        let mut nodes = vec![node.clone()];
+        let mut current = node;
+        while let Some(previous) = self.previous.get(current) {
+            nodes.push(previous.clone());
+            current = previous;
+        }
+        nodes.reverse();
+
+        Some(Path {
+            nodes,
+            cost,
+        })
+    }
+}
+
+// Breadth first search, where every edge costs 1. Explores everything reachable from the starts.
+pub fn bfs<N, I, F>(starts: impl IntoIterator<Item=N>, mut neighbours: F) -> Search<N, usize>
+    where N: Eq + Hash + Clone,
+          I: IntoIterator<Item=N>,
+          F: FnMut(&N) -> I {
+    let mut search = Search::new();
+
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.costs.insert(start.clone(), 0).is_none() {
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((current, cost)) = queue.pop_front() {
+        for neighbour in neighbours(&current) {
+            if !search.is_reached(&neighbour) {
+                search.costs.insert(neighbour.clone(), cost + 1);
+                search.previous.insert(neighbour.clone(), current.clone());
+                queue.push_back((neighbour, cost + 1));
+            }
+        }
+    }
+    search
+}
+
+// Dijkstra, for weighted edges. Explores everything reachable from the starts.
+pub fn dijkstra<N, C, I, F>(starts: impl IntoIterator<Item=N>, neighbours: F) -> Search<N, C>
+    where N: Eq + Hash + Clone,
+          C: Cost,
+          I: IntoIterator<Item=(N, C)>,
+          F: FnMut(&N) -> I {
+    explore(starts, neighbours, |_| C::default(), |_| false).0
+}
+
+// A*, for weighted edges. Stops at the first goal reached. The heuristic should never
+// overestimate the remaining cost, otherwise the path found might not be the best one.
+pub fn astar<N, C, I, F, H, G>(starts: impl IntoIterator<Item=N>, neighbours: F, heuristic: H, is_goal: G) -> Option<Path<N, C>>
+    where N: Eq + Hash + Clone,
+          C: Cost,
+          I: IntoIterator<Item=(N, C)>,
+          F: FnMut(&N) -> I,
+          H: FnMut(&N) -> C,
+          G: FnMut(&N) -> bool {
+    let (search, goal) = explore(starts, neighbours, heuristic, is_goal);
+    search.path(&goal?)
+}
+
+fn explore<N, C, I, F, H, G>(starts: impl IntoIterator<Item=N>, mut neighbours: F, mut heuristic: H, mut is_goal: G) -> (Search<N, C>, Option<N>)
+    where N: Eq + Hash + Clone,
+          C: Cost,
+          I: IntoIterator<Item=(N, C)>,
+          F: FnMut(&N) -> I,
+          H: FnMut(&N) -> C,
+          G: FnMut(&N) -> bool {
+    let mut search = Search::new();
+
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if search.costs.insert(start.clone(), C::default()).is_none() {
+            queue.push(Entry(heuristic(&start), C::default(), start));
+        }
+    }
+
+    while let Some(Entry(_, cost, current)) = queue.pop() {
+        // Skip outdated entries. A better path to this node was found after they were queued.
+        if search.cost(&current).is_some_and(|it| it < cost) { continue; }
+
+        if is_goal(&current) { return (search, Some(current)); }
+
+        for (neighbour, edge) in neighbours(&current) {
+            let new_cost = cost + edge;
+            if search.cost(&neighbour).is_none_or(|it| new_cost < it) {
+                search.costs.insert(neighbour.clone(), new_cost);
+                search.previous.insert(neighbour.clone(), current.clone());
+                queue.push(Entry(new_cost + heuristic(&neighbour), new_cost, neighbour));
+            }
+        }
+    }
+    (search, None)
+}
+
+// Node to visit, with its priority and its cost.
+struct Entry<N, C>(C, C, N);
+
+// Reverse order. Smaller priority gets visited first.
+impl<N, C: Ord> Ord for Entry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A -1-> B -2-> C -1-> D, and a direct but more expensive A -10-> C.
+    fn graph(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'A' => vec![('B', 1), ('C', 10)],
+            'B' => vec![('C', 2)],
+            'C' => vec![('D', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        let search = dijkstra(['A'], graph);
+
+        assert_eq!(search.cost(&'C'), Some(3));
+        assert_eq!(search.cost(&'D'), Some(4));
+        assert_eq!(search.path(&'D'), Some(Path { nodes: vec!['A', 'B', 'C', 'D'], cost: 4 }));
+    }
+
+    #[test]
+    fn dijkstra_starts_from_the_closest_start() {
+        // Nodes on a line, from 0 to 10, starting from both ends.
+        let search = dijkstra([0, 10], |it: &i32| [it - 1, it + 1].into_iter().filter(|it| (0..=10).contains(it)).map(|it| (it, 1)));
+
+        assert_eq!(search.cost(&0), Some(0));
+        assert_eq!(search.cost(&10), Some(0));
+        assert_eq!(search.cost(&3), Some(3));
+        assert_eq!(search.cost(&7), Some(3));
+        assert_eq!(search.path(&8).map(|it| it.nodes), Some(vec![10, 9, 8]));
+    }
+
+    #[test]
+    fn dijkstra_does_not_reach_disconnected_nodes() {
+        let search = dijkstra(['B'], graph);
+
+        assert!(!search.is_reached(&'A'));
+        assert_eq!(search.path(&'A'), None);
+        assert_eq!(search.reached().count(), 3);
+    }
+
+    #[test]
+    fn astar_stops_at_the_goal() {
+        // The graph has no end, so the search would never finish without stopping.
+        let mut visited = 0;
+        let path = astar(
+            [0],
+            |it: &i64| {
+                visited += 1;
+                [(it - 1, 1), (it + 1, 1)]
+            },
+            |it| (5 - it).abs(),
+            |it| *it == 5,
+        );
+
+        assert_eq!(path, Some(Path { nodes: vec![0, 1, 2, 3, 4, 5], cost: 5 }));
+        assert_eq!(visited, 5);
+    }
+
+    #[test]
+    fn astar_finds_the_cheapest_path() {
+        let path = astar(['A'], graph, |_| 0, |it| *it == 'C');
+
+        assert_eq!(path, Some(Path { nodes: vec!['A', 'B', 'C'], cost: 3 }));
+    }
+
+    #[test]
+    fn astar_without_reachable_goal() {
+        assert_eq!(astar(['B'], graph, |_| 0, |it| *it == 'A'), None);
+    }
+
+    #[test]
+    fn path_of_a_start_is_the_start() {
+        let search = bfs([(0, 0)], |&(x, y): &(i32, i32)| [(x + 1, y), (x, y + 1)].into_iter().filter(|it| it.0 <= 2 && it.1 <= 2));
+
+        assert_eq!(search.path(&(0, 0)), Some(Path { nodes: vec![(0, 0)], cost: 0 }));
+
+        let path = search.path(&(2, 2)).expect("corner should be reached");
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes.len(), 5);
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(2, 2)));
+        assert!(path.nodes.windows(2).all(|it| (it[1].0 - it[0].0) + (it[1].1 - it[0].1) == 1));
+    }
+}
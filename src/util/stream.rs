@@ -0,0 +1,134 @@
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{ParseError, ParseResult, ReadError, Solution};
+use super::parameters::Parameters;
+
+// Size of the buffer of a timed reader. Same as the default of BufReader.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// Parsed one line at a time, so the whole file never has to be in memory. Only the parsed
+// value is kept. Lines are pushed into a state, which is turned into the value at the end.
+pub trait TryFromLineStream: Sized {
+    type State: Default;
+
+    fn try_push_line(state: &mut Self::State, line: &str) -> ParseResult<()>;
+    fn try_finish(state: Self::State) -> ParseResult<Self>;
+
+    // Named constants the lines are folded with, if any. Lines are not kept, so parameters are
+    // set before the first one.
+    fn parameters(_state: &mut Self::State) -> Option<&mut dyn Parameters> {
+        None
+    }
+}
+
+pub trait FromLineStream {
+    fn from_line_stream<R: BufRead>(reader: R) -> Self;
+}
+
+impl<T> FromLineStream for T
+    where T: TryFromLineStream {
+    fn from_line_stream<R: BufRead>(reader: R) -> Self {
+        read_stream(Path::new("<stream>"), reader, T::State::default()).unwrap_or_else(|it| panic!("{it}"))
+    }
+}
+
+// Solution which can also run on a streamed input, in constant memory. The streamed input is
+// a type of its own, which folds the lines as they come, and only keeps what the answers need.
+// The usual input stays the one which is measured, so timings don't depend on streaming.
+pub trait StreamSolution: Solution {
+    type Stream: TryFromLineStream;
+
+    fn stream_part_1(stream: &Self::Stream) -> Self::Part1;
+    fn stream_part_2(stream: &Self::Stream) -> Self::Part2;
+}
+
+// Errors are located with the line number, since lines don't outlive their parsing.
+pub fn read_stream<T, R>(path: &Path, mut reader: R, mut state: T::State) -> Result<T, ReadError>
+    where T: TryFromLineStream,
+          R: BufRead {
+    // The same buffer is used for every line.
+    let mut buffer = String::new();
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer).map_err(|it| ReadError::Io(path.into(), it))? == 0 { break; }
+        number += 1;
+
+        // Same line endings as str::lines.
+        let line = buffer.strip_suffix('\n').map(|it| it.strip_suffix('\r').unwrap_or(it)).unwrap_or(&buffer);
+        T::try_push_line(&mut state, line).map_err(|it| ReadError::Parse(it.locate_line(path, line, number)))?;
+    }
+
+    T::try_finish(state).map_err(|it| ReadError::Parse(it.locate(path, "")))
+}
+
+impl ParseError {
+    fn locate_line(self, path: &Path, line: &str, number: usize) -> Self {
+        // Locating inside a single line gives the column. The line number is known.
+        let mut error = self.locate(path, line);
+        if let Some(location) = &mut error.location {
+            location.line = Some((number, location.line.map(|(_, column)| column).unwrap_or(1)));
+        }
+        error
+    }
+}
+
+// Buffered reader, which keeps track of the time spent reading. Parsing a stream reads along
+// the way, and reading is not part of the measurements.
+pub struct TimedReader<R> {
+    inner: R,
+    buffer: Box<[u8]>,
+    start: usize,
+    end: usize,
+    time: Duration,
+}
+
+impl<R> TimedReader<R>
+    where R: Read {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            time: Duration::ZERO,
+        }
+    }
+
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+}
+
+impl<R> Read for TimedReader<R>
+    where R: Read {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl<R> BufRead for TimedReader<R>
+    where R: Read {
+    // Only reads from the inner reader are timed, once the buffer is empty.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.start == self.end {
+            let start = Instant::now();
+            let count = self.inner.read(&mut self.buffer);
+            self.time += start.elapsed();
+
+            self.start = 0;
+            self.end = count?;
+        }
+        Ok(&self.buffer[self.start..self.end])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.start = (self.start + amount).min(self.end);
+    }
+}
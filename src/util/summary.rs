@@ -0,0 +1,433 @@
+use std::fmt::{self, Display, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use super::alloc::{self, Allocations};
+use super::answers::Answers;
+use super::{Execution, Puzzle, ReadError};
+
+// Message of the todo! macro. Stubs are not run, but a solution may still have a part left
+// to write.
+const TODO_MESSAGE: &str = "not yet implemented";
+
+// Longest answer shown in the table. Longer ones are cut.
+const ANSWER_WIDTH: usize = 20;
+const ALLOCATIONS_WIDTH: usize = 11;
+
+// Version of the JSON layout. Bump it when a field changes, so consumers can tell. The CSV
+// layout is described by its header.
+const SCHEMA_VERSION: u32 = 2;
+const CSV_HEADER: &str = "day,status,part_1,part_2,parse_ns,part_1_ns,part_2_ns,total_ns,allocations,allocated_bytes,peak_bytes,message";
+
+// How a summary is printed. Text is for humans, JSON and CSV for other tools.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+// A day to run, with its solution.
+pub struct Job {
+    pub day: usize,
+    pub puzzle: Puzzle,
+    pub execute: fn(&Puzzle) -> Result<Execution, ReadError>,
+    // Stubs are not run, so their input is not needed.
+    pub is_stub: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Status {
+    Ok,
+    // Answers do not match the known ones.
+    Fail,
+    NotImplemented,
+    Panic(String),
+    Error(String),
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Fail => "fail",
+            Status::NotImplemented => "not implemented",
+            Status::Panic(_) => "panic",
+            Status::Error(_) => "error",
+        }
+    }
+
+    // Same as the name, without spaces. Part of the JSON and CSV layouts.
+    fn key(&self) -> String {
+        self.name().replace(' ', "_")
+    }
+
+    fn message(&self) -> Option<&str> {
+        match self {
+            Status::Panic(message) | Status::Error(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    // Stubs are expected, so they are not failures.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Fail | Status::Panic(_) | Status::Error(_))
+    }
+}
+
+#[derive(Debug)]
+pub struct Row {
+    pub day: usize,
+    pub status: Status,
+    pub execution: Option<Execution>,
+}
+
+// Results of many days, ordered by day.
+#[derive(Debug)]
+pub struct Summary {
+    pub rows: Vec<Row>,
+}
+
+impl Summary {
+    pub fn total(&self) -> Duration {
+        self.rows.iter().filter_map(|it| it.execution.as_ref()).map(|it| it.time()).sum()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.rows.iter().all(|it| !it.status.is_failure())
+    }
+
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.to_string(),
+            Format::Json => self.to_json(),
+            Format::Csv => self.to_csv(),
+        }
+    }
+
+    // Missing values (no execution, no message, allocations not counted) are null.
+    fn to_json(&self) -> String {
+        let mut json = format!("{{\"version\":{SCHEMA_VERSION},\"days\":[");
+        for (index, row) in self.rows.iter().enumerate() {
+            if index > 0 { json.push(','); }
+
+            let execution = row.execution.as_ref();
+            let answer = |answer: Option<&String>| answer.map(|it| json_string(it)).unwrap_or_else(|| "null".into());
+            let time = |time: Option<Duration>| time.map(|it| it.as_nanos().to_string()).unwrap_or_else(|| "null".into());
+            let allocations = |allocations: Option<Allocations>| {
+                allocations
+                    .map(|it| format!("{{\"count\":{},\"bytes\":{},\"peak\":{}}}", it.count, it.bytes, it.peak))
+                    .unwrap_or_else(|| "null".into())
+            };
+            let _ = write!(
+                json,
+                "{{\"day\":{},\"status\":{},\"part_1\":{},\"part_2\":{},\"parse_ns\":{},\"part_1_ns\":{},\"part_2_ns\":{},\"total_ns\":{},\
+                 \"parse_allocations\":{},\"part_1_allocations\":{},\"part_2_allocations\":{},\"message\":{}}}",
+                row.day,
+                json_string(&row.status.key()),
+                answer(execution.map(|it| &it.part_1)),
+                answer(execution.map(|it| &it.part_2)),
+                time(execution.map(|it| it.parse_time)),
+                time(execution.map(|it| it.part_1_time)),
+                time(execution.map(|it| it.part_2_time)),
+                time(execution.map(|it| it.time())),
+                allocations(execution.and_then(|it| it.parse_allocations)),
+                allocations(execution.and_then(|it| it.part_1_allocations)),
+                allocations(execution.and_then(|it| it.part_2_allocations)),
+                row.status.message().map(json_string).unwrap_or_else(|| "null".into()),
+            );
+        }
+        let _ = writeln!(json, "],\"total_ns\":{}}}", self.total().as_nanos());
+        json
+    }
+
+    // Missing values are empty. There is no total row, since it would not be a day. Allocations
+    // are the total of all phases, with the highest peak.
+    fn to_csv(&self) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        for row in &self.rows {
+            let execution = row.execution.as_ref();
+            let time = |time: Option<Duration>| time.map(|it| it.as_nanos().to_string()).unwrap_or_default();
+            let allocations = execution.and_then(|it| it.allocations());
+            let count = |count: fn(&Allocations) -> usize| allocations.as_ref().map(|it| count(it).to_string()).unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                row.day,
+                row.status.key(),
+                csv_field(execution.map(|it| it.part_1.as_str()).unwrap_or_default()),
+                csv_field(execution.map(|it| it.part_2.as_str()).unwrap_or_default()),
+                time(execution.map(|it| it.parse_time)),
+                time(execution.map(|it| it.part_1_time)),
+                time(execution.map(|it| it.part_2_time)),
+                time(execution.map(|it| it.time())),
+                count(|it| it.count),
+                count(|it| it.bytes),
+                count(|it| it.peak),
+                csv_field(row.status.message().unwrap_or_default()),
+            );
+        }
+        csv
+    }
+}
+
+// Run the days on a pool of threads. A single thread gives the cleanest timings. Panics are
+// caught, so a broken day does not stop the others. Allocation counters are shared, so days
+// are run one at a time when allocations are counted.
+pub fn run(jobs: Vec<Job>, threads: usize, answers: Option<&Answers>) -> Summary {
+    let threads = if alloc::is_enabled() { 1 } else { threads };
+
+    // The default hook prints every panic. They are reported in the summary instead.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let next = AtomicUsize::new(0);
+    let rows = Mutex::new(Vec::with_capacity(jobs.len()));
+    thread::scope(|s| {
+        for _ in 0..threads.clamp(1, jobs.len().max(1)) {
+            s.spawn(|| {
+                while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let row = run_job(job, answers);
+                    rows.lock().expect("no thread should panic while holding the rows").push(row);
+                }
+            });
+        }
+    });
+
+    panic::set_hook(hook);
+
+    let mut rows = rows.into_inner().expect("no thread should panic while holding the rows");
+    rows.sort_by_key(|it| it.day);
+    Summary { rows }
+}
+
+fn run_job(job: &Job, answers: Option<&Answers>) -> Row {
+    if job.is_stub {
+        return Row {
+            day: job.day,
+            status: Status::NotImplemented,
+            execution: None,
+        };
+    }
+
+    let (status, execution) = match panic::catch_unwind(AssertUnwindSafe(|| (job.execute)(&job.puzzle))) {
+        Ok(Ok(execution)) => {
+            let is_fail = answers
+                .map(|it| it.check(job.day, &execution.part_1, &execution.part_2))
+                .is_some_and(|(part_1, part_2)| part_1.is_fail() || part_2.is_fail());
+
+            (if is_fail { Status::Fail } else { Status::Ok }, Some(execution))
+        }
+        Ok(Err(error)) => (Status::Error(error.to_string()), None),
+        Err(payload) => {
+            // Panics carry either a static or a formatted message.
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|it| it.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+
+            if message.starts_with(TODO_MESSAGE) { (Status::NotImplemented, None) } else { (Status::Panic(message), None) }
+        }
+    };
+
+    Row {
+        day: job.day,
+        status,
+        execution,
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Allocations of all phases, only when they are counted.
+        let is_counted = self.rows.iter().filter_map(|it| it.execution.as_ref()).any(|it| it.allocations().is_some());
+        let allocations = |row: &Row| match row.execution.as_ref().and_then(|it| it.allocations()) {
+            Some(allocations) => format!("{:>ALLOCATIONS_WIDTH$}  ", allocations.count),
+            None if is_counted => format!("{:>ALLOCATIONS_WIDTH$}  ", "-"),
+            None => String::new(),
+        };
+
+        write!(f, "{:>3}  {:<ANSWER_WIDTH$}  {:<ANSWER_WIDTH$}  {:>16}  {:>16}  ", "Day", "Part 1", "Part 2", "Parse", "Solve")?;
+        if is_counted { write!(f, "{:>ALLOCATIONS_WIDTH$}  ", "Allocations")?; }
+        writeln!(f, "Status")?;
+        for row in &self.rows {
+            match &row.execution {
+                Some(execution) => writeln!(
+                    f,
+                    "{:>3}  {:<ANSWER_WIDTH$}  {:<ANSWER_WIDTH$}  {:>13} ns  {:>13} ns  {}{}",
+                    row.day,
+                    cell(&execution.part_1),
+                    cell(&execution.part_2),
+                    execution.parse_time.as_nanos(),
+                    (execution.part_1_time + execution.part_2_time).as_nanos(),
+                    allocations(row),
+                    row.status.name()
+                )?,
+                None => writeln!(
+                    f,
+                    "{:>3}  {:<ANSWER_WIDTH$}  {:<ANSWER_WIDTH$}  {:>16}  {:>16}  {}{}",
+                    row.day,
+                    "-",
+                    "-",
+                    "-",
+                    "-",
+                    allocations(row),
+                    row.status.name()
+                )?,
+            }
+        }
+        writeln!(f, "Total time : {} ns", self.total().as_nanos())?;
+
+        // Messages are too long for the table.
+        for row in &self.rows {
+            match &row.status {
+                Status::Panic(message) => writeln!(f, "Day {} panicked : {message}", row.day)?,
+                Status::Error(message) => writeln!(f, "Day {} failed : {message}", row.day)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// Answers on a single line, cut to fit. Drawings can't be shown in a table.
+fn cell(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("({} lines)", answer.lines().count())
+    } else if answer.chars().count() > ANSWER_WIDTH {
+        format!("{}…", answer.chars().take(ANSWER_WIDTH - 1).collect::<String>())
+    } else {
+        answer.into()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            char if char.is_control() => { let _ = write!(json, "\\u{:04x}", char as u32); }
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+
+// Quoted only when needed, as in RFC 4180.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(part_1: &str, part_2: &str) -> Execution {
+        Execution {
+            part_1: part_1.into(),
+            part_2: part_2.into(),
+            parse_time: Duration::from_nanos(1),
+            part_1_time: Duration::from_nanos(20),
+            part_2_time: Duration::from_nanos(300),
+            parse_allocations: None,
+            part_1_allocations: None,
+            part_2_allocations: None,
+        }
+    }
+
+    // An answer with every special character, a stub, an error and a counted day.
+    fn summary() -> Summary {
+        let counted = Execution {
+            parse_allocations: Some(Allocations { count: 1, bytes: 10, peak: 10 }),
+            part_1_allocations: Some(Allocations { count: 2, bytes: 5, peak: 15 }),
+            part_2_allocations: Some(Allocations { count: 0, bytes: 0, peak: 0 }),
+            ..execution("1", "2")
+        };
+
+        Summary {
+            rows: vec![
+                Row { day: 10, status: Status::Ok, execution: Some(execution("a,\"b\"", "#.\n.#")) },
+                Row { day: 16, status: Status::NotImplemented, execution: None },
+                Row { day: 17, status: Status::Error("input, \"missing\"".into()), execution: None },
+                Row { day: 18, status: Status::Fail, execution: Some(counted) },
+            ],
+        }
+    }
+
+    #[test]
+    fn json_escaping() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(json_string("a\"b\\c"), r#""a\"b\\c""#);
+        assert_eq!(json_string("#.\n.#\r\t"), r##""#.\n.#\r\t""##);
+        assert_eq!(json_string("\u{0}\u{1b}\u{7f}é"), r#""\u0000\u001b\u007fé""#);
+    }
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), r#""a,b""#);
+        assert_eq!(csv_field("a \"b\""), r#""a ""b""""#);
+        assert_eq!(csv_field("#.\n.#"), "\"#.\n.#\"");
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn json() {
+        let json = summary().format(Format::Json);
+
+        assert!(json.starts_with(&format!("{{\"version\":{SCHEMA_VERSION},\"days\":[")));
+        assert!(json.contains(r##"{"day":10,"status":"ok","part_1":"a,\"b\"","part_2":"#.\n.#","parse_ns":1,"part_1_ns":20,"part_2_ns":300,"total_ns":321,"parse_allocations":null,"part_1_allocations":null,"part_2_allocations":null,"message":null}"##));
+        assert!(json.contains(r#"{"day":16,"status":"not_implemented","part_1":null,"part_2":null,"parse_ns":null,"part_1_ns":null,"part_2_ns":null,"total_ns":null,"parse_allocations":null,"part_1_allocations":null,"part_2_allocations":null,"message":null}"#));
+        assert!(json.contains(r#""day":17,"status":"error","#));
+        assert!(json.contains(r#""message":"input, \"missing\""}"#));
+        assert!(json.contains(r#""parse_allocations":{"count":1,"bytes":10,"peak":10},"part_1_allocations":{"count":2,"bytes":5,"peak":15}"#));
+        assert!(json.ends_with("],\"total_ns\":642}\n"));
+    }
+
+    #[test]
+    fn csv() {
+        let csv = summary().format(Format::Csv);
+        let expected = [
+            CSV_HEADER,
+            "10,ok,\"a,\"\"b\"\"\",\"#.\n.#\",1,20,300,321,,,,",
+            "16,not_implemented,,,,,,,,,,",
+            "17,error,,,,,,,,,,\"input, \"\"missing\"\"\"",
+            "18,fail,1,2,1,20,300,321,3,15,15,",
+        ];
+
+        assert_eq!(csv, format!("{}\n", expected.join("\n")));
+    }
+
+    #[test]
+    fn text() {
+        let text = summary().to_string();
+
+        assert!(text.lines().next().is_some_and(|it| it.contains("Allocations")));
+        assert!(text.contains("(2 lines)"));
+        assert!(text.contains("Day 17 failed : input, \"missing\""));
+        assert!(text.contains("Total time : 642 ns"));
+        assert_eq!(cell(&"9".repeat(ANSWER_WIDTH + 1)), format!("{}…", "9".repeat(ANSWER_WIDTH - 1)));
+    }
+
+    #[test]
+    fn failures() {
+        assert!(!summary().is_success());
+        assert!(!Status::NotImplemented.is_failure());
+        assert!(Status::Panic(String::new()).is_failure());
+    }
+}
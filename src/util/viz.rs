@@ -0,0 +1,141 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::vec2;
+
+use super::Vec2;
+
+type Position = Vec2<isize>;
+
+const DEFAULT_FPS: u32 = 30;
+
+// Frames bigger than this are cropped, unless a viewport is given. Fits most terminals.
+const DEFAULT_MAX_WIDTH: usize = 160;
+const DEFAULT_MAX_HEIGHT: usize = 50;
+
+// ANSI escape codes.
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CLEAR_LINE_END: &str = "\x1b[K";
+const CLEAR_SCREEN_END: &str = "\x1b[J";
+const CURSOR_HOME: &str = "\x1b[H";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+// Something drawn in a terminal, one char per cell. Y points down.
+pub trait Frame {
+    // Top left and bottom right corners (inclusive) of what is worth drawing.
+    fn bounds(&self) -> (Position, Position);
+    fn cell(&self, position: Position) -> char;
+}
+
+// Part of a frame which is drawn.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Viewport {
+    pub origin: Position,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    pub fn new(origin: Position, width: usize, height: usize) -> Self {
+        Self {
+            origin,
+            width,
+            height,
+        }
+    }
+
+    fn of(frame: &dyn Frame) -> Self {
+        let (min, max) = frame.bounds();
+        let width = (max.x() - min.x() + 1).max(0) as usize;
+        let height = (max.y() - min.y() + 1).max(0) as usize;
+        Self::new(min, width, height)
+    }
+
+    // Keeps the top left corner.
+    fn crop(self, width: usize, height: usize) -> Self {
+        Self::new(self.origin, self.width.min(width), self.height.min(height))
+    }
+}
+
+// Draws frames in a terminal, at a steady pace. The screen is redrawn in place.
+pub struct Renderer {
+    output: Box<dyn Write>,
+    frame_time: Duration,
+    viewport: Option<Viewport>,
+    max_width: usize,
+    max_height: usize,
+    last_frame: Option<Instant>,
+}
+
+impl Renderer {
+    pub fn new(output: impl Write + 'static) -> Self {
+        Self {
+            output: Box::new(output),
+            frame_time: Duration::from_secs(1) / DEFAULT_FPS,
+            viewport: None,
+            max_width: DEFAULT_MAX_WIDTH,
+            max_height: DEFAULT_MAX_HEIGHT,
+            last_frame: None,
+        }
+    }
+
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+
+    // Zero means as fast as possible.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.frame_time = if fps == 0 { Duration::ZERO } else { Duration::from_secs(1) / fps };
+        self
+    }
+
+    // Draw only this part of the frames. Otherwise, frames are drawn from their bounds, cropped.
+    pub fn viewport(mut self, viewport: Option<Viewport>) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    pub fn max_size(mut self, width: usize, height: usize) -> Self {
+        self.max_width = width;
+        self.max_height = height;
+        self
+    }
+
+    pub fn render(&mut self, frame: &dyn Frame) -> io::Result<()> {
+        let viewport = self.viewport.unwrap_or_else(|| Viewport::of(frame).crop(self.max_width, self.max_height));
+
+        // Draw the whole frame at once, to avoid flickering.
+        let mut screen = String::with_capacity((viewport.width + CLEAR_LINE_END.len() + 1) * viewport.height);
+        if self.last_frame.is_none() {
+            screen.push_str(HIDE_CURSOR);
+            screen.push_str(CLEAR_SCREEN);
+        }
+        screen.push_str(CURSOR_HOME);
+        for y in 0..viewport.height as isize {
+            for x in 0..viewport.width as isize {
+                screen.push(frame.cell(viewport.origin + vec2!(x, y)));
+            }
+            screen.push_str(CLEAR_LINE_END);
+            screen.push('\n');
+        }
+        screen.push_str(CLEAR_SCREEN_END);
+
+        // Wait for the previous frame to have been shown long enough.
+        if let Some(last_frame) = self.last_frame {
+            thread::sleep(self.frame_time.saturating_sub(last_frame.elapsed()));
+        }
+        self.output.write_all(screen.as_bytes())?;
+        self.output.flush()?;
+        self.last_frame = Some(Instant::now());
+        Ok(())
+    }
+
+    // Leave the last frame on screen, and give the cursor back.
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.last_frame = None;
+        write!(self.output, "{SHOW_CURSOR}")?;
+        self.output.flush()
+    }
+}
@@ -0,0 +1,164 @@
+use advent_of_code_2022::*;
+use advent_of_code_2022::util::answers::Answers;
+use advent_of_code_2022::util::parameters::{Manifest, Parameters};
+use std::time::Duration;
+
+use advent_of_code_2022::util::stream::StreamSolution;
+use advent_of_code_2022::util::summary;
+use advent_of_code_2022::util::{benchmark_stream, execute, execute_stream, read, Puzzle, Solution, Source, TryFromLines};
+
+// Run a solution against its puzzle example, and compare with the expected answers.
+fn check<S>(day: usize)
+    where S: Solution {
+    let answers: Answers = read("examples/answers.txt").unwrap_or_else(|it| panic!("{it}"));
+    let answers = answers.day(day).unwrap_or_else(|| panic!("day {day} should have expected answers"));
+
+    let manifest: Manifest = read("examples/parameters.txt").unwrap_or_else(|it| panic!("{it}"));
+    let puzzle = Puzzle::new(Source::File(format!("examples/day{day}.txt").into())).parameters(manifest.day(day));
+    let execution = execute::<S>(&puzzle).unwrap_or_else(|it| panic!("{it}"));
+
+    assert_eq!(answers.part_1.as_deref(), Some(execution.part_1.as_str()), "day {day}, part 1");
+    assert_eq!(answers.part_2.as_deref(), Some(execution.part_2.as_str()), "day {day}, part 2");
+}
+
+// Streaming the example should give the same answers as reading it whole.
+fn check_stream<S>(day: usize)
+    where S: StreamSolution {
+    let manifest: Manifest = read("examples/parameters.txt").unwrap_or_else(|it| panic!("{it}"));
+    let puzzle = Puzzle::new(Source::File(format!("examples/day{day}.txt").into())).parameters(manifest.day(day));
+    let execution = execute::<S>(&puzzle).unwrap_or_else(|it| panic!("{it}"));
+    let streamed = execute_stream::<S>(&puzzle).unwrap_or_else(|it| panic!("{it}"));
+
+    assert_eq!(execution.part_1, streamed.part_1, "day {day}, part 1");
+    assert_eq!(execution.part_2, streamed.part_2, "day {day}, part 2");
+    // Also compares both ways of parsing.
+    benchmark_stream::<S>(&puzzle, Duration::from_millis(1)).unwrap_or_else(|it| panic!("{it}"));
+}
+
+#[test]
+fn day1() { check::<day1::Day1>(1); }
+
+#[test]
+fn day1_stream() { check_stream::<day1::Day1>(1); }
+
+#[test]
+fn day2() { check::<day2::Day2>(2); }
+
+#[test]
+fn day2_stream() { check_stream::<day2::Day2>(2); }
+
+#[test]
+fn day3() { check::<day3::Day3>(3); }
+
+#[test]
+fn day4() { check::<day4::Day4>(4); }
+
+#[test]
+fn day4_stream() { check_stream::<day4::Day4>(4); }
+
+#[test]
+fn day5() { check::<day5::Day5>(5); }
+
+#[test]
+fn day6() { check::<day6::Day6>(6); }
+
+#[test]
+fn day7() { check::<day7::Day7>(7); }
+
+#[test]
+fn day8() { check::<day8::Day8>(8); }
+
+#[test]
+fn day9() { check::<day9::Day9>(9); }
+
+#[test]
+fn day9_stream() { check_stream::<day9::Day9>(9); }
+
+#[test]
+fn day10() { check::<day10::Day10>(10); }
+
+#[test]
+fn day10_stream() { check_stream::<day10::Day10>(10); }
+
+#[test]
+fn day11() { check::<day11::Day11>(11); }
+
+// A zero divisor or no rounds would break the solution.
+#[test]
+fn day11_parameters() {
+    let mut parameters = day11::Parameters::default();
+
+    for name in ["part_1_rounds", "part_1_relief", "part_2_rounds"] {
+        assert!(parameters.set(name, "0").is_err(), "{name} should not be 0");
+        assert!(parameters.set(name, "1").is_ok());
+    }
+}
+
+#[test]
+fn day12() { check::<day12::Day12>(12); }
+
+#[test]
+fn day13() { check::<day13::Day13>(13); }
+
+#[test]
+fn day14() { check::<day14::Day14>(14); }
+
+#[test]
+fn day15() { check::<day15::Day15>(15); }
+
+#[test]
+#[ignore = "day 16 is not solved yet"]
+fn day16() { check::<day16::Day16>(16); }
+
+#[test]
+fn day17() { check::<day17::Day17>(17); }
+
+#[test]
+fn day18() { check::<day18::Day18>(18); }
+
+#[test]
+fn day19() { check::<day19::Day19>(19); }
+
+#[test]
+fn day20() { check::<day20::Day20>(20); }
+
+#[test]
+fn day21() { check::<day21::Day21>(21); }
+
+// The human is on the right of both operations, so they are inverted the other way.
+#[test]
+fn day21_human_on_the_right() {
+    let lines = ["root: aaaa + bbbb", "aaaa: eeee / cccc", "cccc: dddd - humn", "eeee: 36", "dddd: 20", "bbbb: 3", "humn: 2"];
+    let input = day21::Input::try_from_lines(&lines).unwrap_or_else(|it| panic!("{it}"));
+
+    // 36 / (20 - 2) + 3
+    assert_eq!(day21::Day21::part_1(&input), 5);
+    // 36 / (20 - h) = 3
+    assert_eq!(day21::Day21::part_2(&input), 8);
+}
+
+#[test]
+fn day21_division_by_zero() {
+    let lines = ["root: aaaa + humn", "aaaa: humn / zero", "zero: 0", "humn: 1"];
+    let error = day21::Input::try_from_lines(&lines).err().map(|it| it.to_string());
+
+    assert_eq!(error.as_deref(), Some("monkey should not divide by zero (found \"humn / zero\")"));
+}
+
+// Stubs are reported without being run, even when their input is missing.
+#[test]
+fn stubs() {
+    let jobs = [16, 22, 23, 24, 25]
+        .into_iter()
+        .map(|day| summary::Job {
+            day,
+            puzzle: Puzzle::new(Source::File(format!("missing/day{day}.txt").into())),
+            execute: SOLUTIONS[day - 1].execute,
+            is_stub: SOLUTIONS[day - 1].is_stub,
+        })
+        .collect();
+    let summary = summary::run(jobs, 1, None);
+
+    assert_eq!(summary.rows.len(), 5);
+    assert!(summary.rows.iter().all(|it| matches!(it.status, summary::Status::NotImplemented)));
+}